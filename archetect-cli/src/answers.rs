@@ -0,0 +1,198 @@
+use std::path::Path;
+use std::process::Command;
+
+use archetect::config::{AnswerConfig, AnswerConfigError, AnswerDocument, AnswerInfo};
+use archetect::{Archetect, ArchetectError};
+use clap::ArgMatches;
+use linked_hash_map::LinkedHashMap;
+use log::{info, warn};
+use serde_json::Value;
+
+pub fn handle_answers_command(archetect: &Archetect, matches: &ArgMatches) -> Result<(), ArchetectError> {
+    if let Some(matches) = matches.subcommand_matches("profile") {
+        return handle_profile_command(archetect, matches);
+    }
+
+    let (subcommand, matches) = match matches.subcommand() {
+        (subcommand, Some(matches)) => (subcommand, matches),
+        _ => return Ok(()),
+    };
+    let project = matches.is_present("project");
+    let path = if project {
+        let directory = std::env::current_dir()?;
+        AnswerConfig::find(&directory).unwrap_or_else(|| directory.join(".archetect.yml"))
+    } else {
+        archetect.layout().answers_config()
+    };
+    // Profiles only apply to the user answers file.
+    let profile = if project { None } else { matches.value_of("profile") };
+    let answer_config_error = |cause| answer_config_error(&path, cause);
+
+    match subcommand {
+        "list" => {
+            for (identifier, answer_info) in &load_answers(&path, profile)? {
+                println!("{}", format_answer(identifier, answer_info));
+            }
+        }
+        "get" => {
+            let identifier = matches.value_of("identifier").unwrap();
+            let answers = load_answers(&path, profile)?;
+            match lookup_value(&answers, identifier) {
+                Some(Value::String(value)) => println!("{}", value),
+                Some(value) => println!("{}", value),
+                None => {
+                    return Err(answer_config_error(AnswerConfigError::MissingAnswer(
+                        identifier.to_owned(),
+                    )))
+                }
+            }
+        }
+        "set" => {
+            let mut document = load_document(&path)?;
+            for value in matches.values_of("answers").unwrap() {
+                let (identifier, answer_info) = AnswerInfo::parse(value).unwrap();
+                document.set_answer(profile, &identifier, &answer_info);
+            }
+            save_document(&path, &document, profile)?;
+        }
+        "unset" => {
+            let mut document = load_document(&path)?;
+            for identifier in matches.values_of("identifiers").unwrap() {
+                if !document.unset_answer(profile, identifier) {
+                    warn!("There is no answer for '{}' to remove.", identifier);
+                }
+            }
+            save_document(&path, &document, profile)?;
+        }
+        "edit" => {
+            if !path.exists() {
+                AnswerDocument::parse("answers: {}\n")
+                    .save(&path)
+                    .map_err(answer_config_error)?;
+            }
+            let editor = std::env::var("VISUAL")
+                .or_else(|_| std::env::var("EDITOR"))
+                .unwrap_or_else(|_| "vi".to_owned());
+            let mut words = editor.split_whitespace();
+            let program = words.next().unwrap_or(&editor);
+            let status = Command::new(program).args(words).arg(&path).status()?;
+            if !status.success() {
+                return Err(std::io::Error::other(format!("{} exited with {}", editor, status)).into());
+            }
+            AnswerConfig::load(&path)
+                .and_then(|answer_config| answer_config.profile_answers(profile))
+                .map_err(answer_config_error)?;
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+fn handle_profile_command(archetect: &Archetect, matches: &ArgMatches) -> Result<(), ArchetectError> {
+    let path = archetect.layout().answers_config();
+
+    match matches.subcommand() {
+        ("list", Some(_)) => {
+            let answer_config = load_config(&path)?;
+            for (name, profile) in answer_config.profiles() {
+                let mut line = name.to_owned();
+                if let Some(inherits) = profile.inherits() {
+                    line.push_str(&format!(" (inherits {})", inherits));
+                }
+                if answer_config.default_profile() == Some(name.as_str()) {
+                    line.push_str(" [default]");
+                }
+                println!("{}", line);
+            }
+        }
+        ("show", Some(matches)) => {
+            let profile = matches.value_of("name").or_else(|| matches.value_of("profile"));
+            let answers = load_answers(&path, profile)?;
+            if !answers.is_empty() {
+                print!("{}", serde_yaml::to_string(&answers).unwrap());
+            }
+        }
+        ("set", Some(matches)) => {
+            let name = matches.value_of("name").unwrap();
+            let mut document = load_document(&path)?;
+            if let Some(values) = matches.values_of("answers") {
+                for (identifier, answer_info) in values.map(|value| AnswerInfo::parse(value).unwrap()) {
+                    document.set_answer(Some(name), &identifier, &answer_info);
+                }
+            }
+            if let Some(inherits) = matches.value_of("inherits") {
+                document.set_profile_inherits(name, inherits);
+            }
+            if matches.is_present("default") {
+                document.set_default_profile(name);
+            }
+            save_document(&path, &document, Some(name))?;
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+fn answer_config_error(path: &Path, cause: AnswerConfigError) -> ArchetectError {
+    ArchetectError::AnswerConfigError {
+        source: path.display().to_string(),
+        cause,
+    }
+}
+
+fn load_config(path: &Path) -> Result<AnswerConfig, ArchetectError> {
+    match AnswerConfig::load(path) {
+        Ok(answer_config) => Ok(answer_config),
+        Err(AnswerConfigError::MissingError) if !path.exists() => Ok(AnswerConfig::default()),
+        Err(cause) => Err(answer_config_error(path, cause)),
+    }
+}
+
+fn load_answers(path: &Path, profile: Option<&str>) -> Result<LinkedHashMap<String, AnswerInfo>, ArchetectError> {
+    load_config(path)?
+        .profile_answers(profile)
+        .map_err(|cause| answer_config_error(path, cause))
+}
+
+/// Reads an answers file to be edited in place, which only YAML files can be.
+fn load_document(path: &Path) -> Result<AnswerDocument, ArchetectError> {
+    if let Some("json") | Some("toml") = path.extension().and_then(|extension| extension.to_str()) {
+        return Err(answer_config_error(
+            path,
+            AnswerConfigError::WriteError("only YAML answer files can be changed; edit this one by hand".to_owned()),
+        ));
+    }
+    AnswerDocument::load(path).map_err(|cause| answer_config_error(path, cause))
+}
+
+/// Saves an edited answers file, as long as it still reads as answers and `profile` still resolves.
+fn save_document(path: &Path, document: &AnswerDocument, profile: Option<&str>) -> Result<(), ArchetectError> {
+    document
+        .config()
+        .and_then(|answer_config| answer_config.profile_answers(profile))
+        .and_then(|_| document.save(path))
+        .map_err(|cause| answer_config_error(path, cause))?;
+    info!("Updated {}", path.display());
+    Ok(())
+}
+
+/// Formats an answer as it would be given with `--answer`.
+fn format_answer(identifier: &str, answer_info: &AnswerInfo) -> String {
+    match (answer_info.value(), answer_info.default()) {
+        (Some(Value::String(value)), _) => format!("{}={}", identifier, value),
+        (Some(value), _) => format!("{}:={}", identifier, value),
+        (None, Some(Value::String(default))) => format!("{} (default: {})", identifier, default),
+        (None, Some(default)) => format!("{} (default: {})", identifier, default),
+        (None, None) => format!("{} (no value)", identifier),
+    }
+}
+
+/// The value of an answer, or with a dotted identifier such as `db.host`, of a field within it.
+fn lookup_value(answers: &LinkedHashMap<String, AnswerInfo>, identifier: &str) -> Option<Value> {
+    let mut segments = identifier.split('.');
+    let mut value = answers.get(segments.next()?)?.value()?;
+    for segment in segments {
+        value = value.get(segment)?;
+    }
+    Some(value.clone())
+}
@@ -1,7 +1,10 @@
-use archetect::config::{AnswerConfig, AnswerConfigError, AnswerInfo};
+use archetect::config::{AnswerConfig, AnswerConfigError, AnswerInfo, AnswerParseError};
+use archetect::events::JsonLinesLogger;
 use archetect::loggerv;
+use archetect::loggerv::Output;
 use clap::{crate_authors, crate_description, crate_version};
 use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use log::{Level, LevelFilter};
 
 pub fn get_matches() -> App<'static, 'static> {
     App::new("archetect")
@@ -24,6 +27,30 @@ pub fn get_matches() -> App<'static, 'static> {
                 .short("o")
                 .long("offline"),
         )
+        .arg(
+            Arg::with_name("headless")
+                .global(true)
+                .long("headless")
+                .conflicts_with("events")
+                .help("Never prompt; report every variable without an answer and exit with an error")
+                .long_help(
+                    "Never prompt for answers. Defaults are used where they exist, and every variable left without \
+                     an answer is reported together before exiting with an error. This is the default when stdin \
+                     is not a terminal.",
+                ),
+        )
+        .arg(
+            Arg::with_name("confirm")
+                .global(true)
+                .long("confirm")
+                .conflicts_with("headless")
+                .help("Review the answers before rendering, changing any of them or aborting")
+                .long_help(
+                    "Once the prompts at the top level of the archetype's script are answered, lists the answers \
+                     to accept them, change any of them by number, which updates the values derived from them, or \
+                     abort before anything is rendered. Archetypes may also ask for this with `confirm: true`.",
+                ),
+        )
         .arg(
             Arg::with_name("answer")
                 .short("a")
@@ -43,6 +70,20 @@ pub fn get_matches() -> App<'static, 'static> {
                     )),
                 }),
         )
+        .arg(
+            Arg::with_name("profile")
+                .long("profile")
+                .takes_value(true)
+                .global(true)
+                .env("ARCHETECT_PROFILE")
+                .value_name("name")
+                .help("Use the named profile from your user answers file")
+                .long_help(
+                    "Use the named profile from your user answers file. A profile's answers are applied over \
+                     those of the profile it inherits from, and over the answers shared by every profile. \
+                     Without this option, the file's default-profile is used, if it names one.",
+                ),
+        )
         .arg(
             Arg::with_name("switches")
                 .short("s")
@@ -53,6 +94,36 @@ pub fn get_matches() -> App<'static, 'static> {
                 .empty_values(true)
                 .help("Enable switches that may trigger functionality within Archetypes")
         )
+        .arg(
+            Arg::with_name("events")
+                .long("events")
+                .takes_value(true)
+                .global(true)
+                .possible_values(&["jsonl"])
+                .value_name("format")
+                .conflicts_with("report")
+                .help("Drives archetect from another program through JSON events on stdout and stdin.")
+                .long_help(
+                    "Drives archetect from another program, such as an IDE. Prompts, rendered paths, commands, \
+                     printed text, log messages and errors are written to stdout as one JSON event per line, and \
+                     the output of commands to stderr. Each prompt event is answered by writing a line of JSON to \
+                     stdin, with null accepting the default.",
+                ),
+        )
+        .arg(
+            Arg::with_name("report")
+                .long("report")
+                .takes_value(true)
+                .global(true)
+                .possible_values(&["json"])
+                .value_name("format")
+                .help("Writes a report of the paths rendered, commands run and answers given to stdout.")
+                .long_help(
+                    "Writes a report of the paths rendered, commands run and answers given to stdout, \
+                     in place of the summary. Everything else, such as log output, prompts, printed text and the \
+                     output of commands, is sent to stderr.",
+                ),
+        )
         .arg(
             Arg::with_name("answer-file")
                 .short("A")
@@ -65,14 +136,24 @@ pub fn get_matches() -> App<'static, 'static> {
                 .help("Supply an answers file as answers to variable questions.")
                 .long_help(
                     "Supply an answers file as answers to variable questions. This option may \
-                     be specified more than once.",
+                     be specified more than once. Files ending in .json or .toml are read as JSON or \
+                     TOML, and any others as YAML. Use - to read answers from stdin.\n\n\
+                     Answers are taken from these sources, each overriding those before it:\n\
+                     \n  1. the user answers file (see 'system layout answers')\
+                     \n  2. the destination's answers file, such as .archetect.yml\
+                     \n  3. ARCHETECT_ANSWER_<NAME> environment variables, where ARCHETECT_ANSWER_DB__HOST answers db.host\
+                     \n  4. --answer-file files, in the order given\
+                     \n  5. --answer values, in the order given\n\n\
+                     Run with -v to see where each answer came from.",
                 )
                 .validator(|af| match AnswerConfig::load(&af) {
+                    _ if af == "-" => Ok(()),
                     Ok(_) => Ok(()),
                     Err(AnswerConfigError::ParseError(_)) => Err(format!("{} has an invalid answer file format", &af)),
                     Err(AnswerConfigError::MissingError) => {
                         Err(format!("{} does not exist or does not contain an answer file", &af))
                     }
+                    Err(_) => Err(format!("{} could not be read as an answer file", &af)),
                 }),
         )
         .subcommand(
@@ -86,10 +167,8 @@ pub fn get_matches() -> App<'static, 'static> {
                 )
                 .arg(
                     Arg::with_name("source")
-                        .short("s")
                         .long("source")
                         .takes_value(true)
-                        .global(true)
                         .help("Catalog source location")
                     ,
                 )
@@ -97,7 +176,6 @@ pub fn get_matches() -> App<'static, 'static> {
                     SubCommand::with_name("add")
                         .arg(
                             Arg::with_name("source")
-                                .short("s")
                                 .long("source")
                                 .takes_value(true)
                                 .help("Archetype source location"),
@@ -150,6 +228,101 @@ pub fn get_matches() -> App<'static, 'static> {
                         ),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("answers")
+                .about("Manage the answers in your user answers file")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .about("Lists your answers, including those of the selected profile")
+                        .arg(project_arg()),
+                )
+                .subcommand(
+                    SubCommand::with_name("get")
+                        .about("Prints the value of an answer")
+                        .arg(
+                            Arg::with_name("identifier")
+                                .help("The variable answered, such as author or db.host")
+                                .takes_value(true)
+                                .required(true),
+                        )
+                        .arg(project_arg()),
+                )
+                .subcommand(
+                    SubCommand::with_name("set")
+                        .about("Sets answers, keeping the file's comments and ordering")
+                        .arg(
+                            Arg::with_name("answers")
+                                .help("Answers to set, in the --answer key=value format")
+                                .takes_value(true)
+                                .multiple(true)
+                                .required(true)
+                                .validator(validate_answer),
+                        )
+                        .arg(project_arg()),
+                )
+                .subcommand(
+                    SubCommand::with_name("unset")
+                        .about("Removes answers, keeping the file's comments and ordering")
+                        .arg(
+                            Arg::with_name("identifiers")
+                                .help("The variables whose answers are removed")
+                                .takes_value(true)
+                                .multiple(true)
+                                .required(true),
+                        )
+                        .arg(project_arg()),
+                )
+                .subcommand(
+                    SubCommand::with_name("edit")
+                        .about("Opens the answers file in $VISUAL or $EDITOR, checking it once closed")
+                        .arg(project_arg()),
+                )
+                .subcommand(
+                    SubCommand::with_name("profile")
+                        .about("Manage named answer profiles")
+                        .setting(AppSettings::SubcommandRequiredElseHelp)
+                        .subcommand(SubCommand::with_name("list").about("Lists the profiles"))
+                        .subcommand(
+                            SubCommand::with_name("show")
+                                .about("Shows the answers a profile gives, including those it inherits")
+                                .arg(
+                                    Arg::with_name("name")
+                                        .help("The profile to show. Defaults to the default profile.")
+                                        .takes_value(true),
+                                ),
+                        )
+                        .subcommand(
+                            SubCommand::with_name("set")
+                                .about("Sets answers in a profile, creating it if needed")
+                                .arg(
+                                    Arg::with_name("name")
+                                        .help("The profile to change")
+                                        .takes_value(true)
+                                        .required(true),
+                                )
+                                .arg(
+                                    Arg::with_name("answers")
+                                        .help("Answers to set, in the --answer key=value format")
+                                        .takes_value(true)
+                                        .multiple(true)
+                                        .validator(validate_answer),
+                                )
+                                .arg(
+                                    Arg::with_name("inherits")
+                                        .long("inherits")
+                                        .takes_value(true)
+                                        .value_name("profile")
+                                        .help("The profile this profile inherits answers from"),
+                                )
+                                .arg(
+                                    Arg::with_name("default")
+                                        .long("default")
+                                        .help("Makes this the profile used when none is selected"),
+                                ),
+                        ),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("cache")
                 .about("Manage/Select from Archetypes cached from Git Repositories")
@@ -157,6 +330,16 @@ pub fn get_matches() -> App<'static, 'static> {
                 .subcommand(SubCommand::with_name("clear"))
                 .subcommand(SubCommand::with_name("pull")),
         )
+        .subcommand(
+            SubCommand::with_name("describe")
+                .about("Documents an Archetype's variables in Markdown")
+                .arg(
+                    Arg::with_name("source")
+                        .help("The Archetype source directory or git URL")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("render")
                 .alias("create")
@@ -176,8 +359,40 @@ pub fn get_matches() -> App<'static, 'static> {
         )
 }
 
+/// Scopes an `answers` subcommand to the current project's answers file, such as `.archetect.yml`.
+fn project_arg() -> Arg<'static, 'static> {
+    Arg::with_name("project")
+        .long("project")
+        .help("Use the answers file in the current directory instead of your user answers file")
+}
+
+fn validate_answer(answer: String) -> Result<(), String> {
+    match AnswerInfo::parse(&answer) {
+        Ok(_) => Ok(()),
+        Err(AnswerParseError::InvalidJson(cause)) => Err(format!("'{}' has an invalid JSON value: {}", answer, cause)),
+        Err(_) => Err(format!("'{}' is not in a proper key=value answer format.", answer)),
+    }
+}
+
 pub fn configure(matches: &ArgMatches) {
-    loggerv::Logger::new()
+    if matches.is_present("events") {
+        let level = match matches.occurrences_of("verbosity") {
+            0 => LevelFilter::Info,
+            1 => LevelFilter::Debug,
+            _ => LevelFilter::Trace,
+        };
+        JsonLinesLogger::new(level).init().unwrap();
+        return;
+    }
+
+    let mut logger = loggerv::Logger::new();
+    if matches.is_present("report") {
+        // Keep stdout clean for the report.
+        for level in &[Level::Info, Level::Debug, Level::Trace] {
+            logger = logger.output(level, Output::Stderr);
+        }
+    }
+    logger
         .verbosity(matches.occurrences_of("verbosity"))
         .level(false)
         .prefix("archetect")
@@ -198,4 +413,7 @@ const VALID_ANSWER_INPUTS: &str = "Supply a key=value pair as an answer to a var
                                    \n'key=value'\
                                    \n'key=\"multi-word value\"'\
                                    \n\"key = 'multi-word value'\"\
+                                   \nkey=[first, second]\
+                                   \nkey:='{\"json\": true}'\
+                                   \nparent.key=value\
                                    ";
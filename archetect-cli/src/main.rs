@@ -1,21 +1,27 @@
+mod answers;
 mod cli;
 
+use archetect::actions::merge::MergeError;
 use archetect::config::{
-    AnswerConfig, AnswerConfigError, AnswerInfo, Catalog, CatalogEntry, CatalogError, CATALOG_FILE_NAME,
+    insert_answer, AnswerConfig, AnswerConfigError, AnswerInfo, Catalog, CatalogEntry, CatalogError, ANSWER_ENV_PREFIX,
+    CATALOG_FILE_NAME,
 };
-use archetect::input::select_from_catalog;
+use archetect::events::{Event, JsonLinesEvents};
+use archetect::input::{select_from_catalog, JsonLinesPrompter, MissingAnswer, PromptError};
+use archetect::report::{PathOutcome, RenderReport};
 use archetect::system::SystemError;
 use archetect::util::{Source, SourceError};
 use archetect::RenderError;
-use archetect::{self, ArchetectError, ArchetypeError};
+use archetect::{self, Archetect, ArchetectError, ArchetypeError};
 use clap::{ArgMatches, Shell};
 //use indoc::indoc;
 use archetect::requirements::RequirementsError;
 use linked_hash_map::LinkedHashMap;
-use log::{error, info, warn};
+use log::{debug, error, info, warn};
 use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 fn main() {
@@ -25,46 +31,27 @@ fn main() {
 
     match execute(matches) {
         Ok(()) => (),
-        Err(error) => handle_archetect_error(error),
+        Err(error) => {
+            handle_archetect_error(error);
+            std::process::exit(1);
+        }
     }
 }
 
 fn execute(matches: ArgMatches) -> Result<(), ArchetectError> {
-    let mut archetect = archetect::Archetect::builder()
+    let mut builder = archetect::Archetect::builder()
         .with_offline(matches.is_present("offline"))
-        .build()?;
-
-    let mut answers = LinkedHashMap::new();
-
-    if let Ok(user_answers) = AnswerConfig::load(archetect.layout().answers_config()) {
-        for (identifier, answer_info) in user_answers.answers() {
-            answers.insert(identifier.to_owned(), answer_info.clone());
-        }
-    }
-
-    if let Some(matches) = matches.values_of("answer-file") {
-        for answer_file in matches {
-            match AnswerConfig::load(answer_file) {
-                Ok(answer_config) => {
-                    for (identifier, answer_info) in answer_config.answers() {
-                        answers.insert(identifier.to_owned(), answer_info.clone());
-                    }
-                }
-                Err(cause) => {
-                    return Err(ArchetectError::AnswerConfigError {
-                        source: answer_file.to_owned(),
-                        cause,
-                    });
-                }
-            }
-        }
-    }
-
-    if let Some(matches) = matches.values_of("answer") {
-        for (identifier, answer_info) in matches.map(|m| AnswerInfo::parse(m).unwrap()) {
-            answers.insert(identifier, answer_info);
-        }
+        .with_confirm(matches.is_present("confirm"))
+        .with_reserved_stdout(matches.value_of("report") == Some("json"));
+    if matches.is_present("events") {
+        builder = builder
+            .with_prompter(JsonLinesPrompter::new())
+            .with_event_listener(JsonLinesEvents);
+    } else if matches.is_present("headless") || !atty::is(atty::Stream::Stdin) {
+        builder = builder.with_headless(true);
     }
+    let mut archetect = builder.build()?;
+    let answer_matches = &matches;
 
     if let Some(matches) = matches.values_of("switches") {
         for switch in matches {
@@ -72,6 +59,10 @@ fn execute(matches: ArgMatches) -> Result<(), ArchetectError> {
         }
     }
 
+    if let Some(matches) = matches.subcommand_matches("answers") {
+        answers::handle_answers_command(&archetect, matches)?;
+    }
+
     if let Some(matches) = matches.subcommand_matches("cache") {
         let git_cache = archetect.layout().git_cache_dir();
         if let Some(_sub_matches) = matches.subcommand_matches("clear") {
@@ -108,18 +99,20 @@ fn execute(matches: ArgMatches) -> Result<(), ArchetectError> {
         }
     }
 
+    if let Some(matches) = matches.subcommand_matches("describe") {
+        let archetype = archetect.load_archetype(matches.value_of("source").unwrap(), None)?;
+        print!("{}", archetype.describe());
+    }
+
     if let Some(matches) = matches.subcommand_matches("render") {
         let source = matches.value_of("source").unwrap();
         let destination = PathBuf::from_str(matches.value_of("destination").unwrap()).unwrap();
 
         let archetype = archetect.load_archetype(source, None)?;
 
-        if let Ok(answer_config) = AnswerConfig::load(destination.clone()) {
-            for (identifier, answer_info) in answer_config.answers() {
-                answers.insert(identifier.to_owned(), answer_info.clone());
-            }
-        }
-        archetype.execute_script(&archetect, &destination, &answers)?;
+        let answers = load_answers(&archetect, answer_matches, &destination)?;
+        let report = archetype.execute_script(&archetect, &destination, &answers)?;
+        print_report(&archetect, &matches, &report);
     }
 
     if let Some(matches) = matches.subcommand_matches("catalog") {
@@ -152,14 +145,9 @@ fn execute(matches: ArgMatches) -> Result<(), ArchetectError> {
 
                         let archetype = archetect.load_archetype(&source, None)?;
 
-                        if let Ok(answer_config) = AnswerConfig::load(destination.clone()) {
-                            for (identifier, answer_info) in answer_config.answers() {
-                                if !answers.contains_key(identifier) {
-                                    answers.insert(identifier.to_owned(), answer_info.clone());
-                                }
-                            }
-                        }
-                        archetype.execute_script(&archetect, &destination, &answers)?;
+                        let answers = load_answers(&archetect, answer_matches, &destination)?;
+                        let report = archetype.execute_script(&archetect, &destination, &answers)?;
+                        print_report(&archetect, &matches, &report);
                         return Ok(());
                     }
                     _ => unreachable!(),
@@ -173,6 +161,151 @@ fn execute(matches: ArgMatches) -> Result<(), ArchetectError> {
     Ok(())
 }
 
+/// Gathers answers from every source, each taking precedence over those before it:
+///
+/// 1. the user's answers file, using the selected profile,
+/// 2. the destination's answers file, such as `.archetect.yml`,
+/// 3. `ARCHETECT_ANSWER_<NAME>` environment variables,
+/// 4. `--answer-file` files, in the order given, with `-` reading stdin,
+/// 5. `--answer` values, in the order given.
+fn load_answers(
+    archetect: &Archetect,
+    matches: &ArgMatches,
+    destination: &Path,
+) -> Result<LinkedHashMap<String, AnswerInfo>, ArchetectError> {
+    let mut answers = LinkedHashMap::new();
+
+    let user_answers = archetect.layout().answers_config();
+    let profile = matches.value_of("profile");
+    let user_answer_error = |cause| ArchetectError::AnswerConfigError {
+        source: user_answers.display().to_string(),
+        cause,
+    };
+    match AnswerConfig::load(&user_answers) {
+        Ok(answer_config) => {
+            let source = match profile.or_else(|| answer_config.default_profile()) {
+                Some(profile) => format!("{} (profile '{}')", user_answers.display(), profile),
+                None => user_answers.display().to_string(),
+            };
+            let profile_answers = answer_config.profile_answers(profile).map_err(user_answer_error)?;
+            add_answers(&mut answers, &source, &profile_answers)?;
+        }
+        Err(_) => {
+            if let Some(profile) = profile {
+                return Err(user_answer_error(AnswerConfigError::MissingProfile(profile.to_owned())));
+            }
+        }
+    }
+
+    if let Ok(answer_config) = AnswerConfig::load(destination) {
+        add_answers(
+            &mut answers,
+            &destination.display().to_string(),
+            answer_config.answers(),
+        )?;
+    }
+
+    let env_source = format!("{}* environment variables", ANSWER_ENV_PREFIX);
+    let env_answers = AnswerConfig::from_env().map_err(|cause| ArchetectError::AnswerConfigError {
+        source: env_source.clone(),
+        cause,
+    })?;
+    add_answers(&mut answers, &env_source, env_answers.answers())?;
+
+    if let Some(answer_files) = matches.values_of("answer-file") {
+        for answer_file in answer_files {
+            let answer_config = if answer_file == "-" {
+                let mut contents = String::new();
+                std::io::stdin().read_to_string(&mut contents)?;
+                AnswerConfig::parse(&contents)
+            } else {
+                AnswerConfig::load(answer_file)
+            };
+            match answer_config {
+                Ok(answer_config) => {
+                    let source = if answer_file == "-" { "stdin" } else { answer_file };
+                    add_answers(&mut answers, source, answer_config.answers())?
+                }
+                Err(cause) => {
+                    return Err(ArchetectError::AnswerConfigError {
+                        source: answer_file.to_owned(),
+                        cause,
+                    });
+                }
+            }
+        }
+    }
+
+    if let Some(values) = matches.values_of("answer") {
+        for (identifier, answer_info) in values.map(|value| AnswerInfo::parse(value).unwrap()) {
+            debug!("Answer for '{}' from --answer", identifier);
+            insert_answer(&mut answers, &identifier, answer_info).map_err(|cause| {
+                ArchetectError::AnswerConfigError {
+                    source: "--answer".to_owned(),
+                    cause,
+                }
+            })?;
+        }
+    }
+
+    Ok(answers)
+}
+
+fn add_answers(
+    answers: &mut LinkedHashMap<String, AnswerInfo>,
+    source: &str,
+    additions: &LinkedHashMap<String, AnswerInfo>,
+) -> Result<(), ArchetectError> {
+    for (identifier, answer_info) in additions {
+        debug!("Answer for '{}' from {}", identifier, source);
+        insert_answer(answers, identifier, answer_info.clone()).map_err(|cause| ArchetectError::AnswerConfigError {
+            source: source.to_owned(),
+            cause,
+        })?;
+    }
+    Ok(())
+}
+
+fn print_report(archetect: &Archetect, matches: &ArgMatches, report: &RenderReport) {
+    if matches.is_present("events") {
+        archetect.emit(Event::Report(report.clone()));
+        return;
+    }
+    if let Some("json") = matches.value_of("report") {
+        println!("{}", serde_json::to_string_pretty(report).unwrap());
+        return;
+    }
+
+    let outcomes = [
+        (PathOutcome::Rendered, "rendered"),
+        (PathOutcome::Overwritten, "overwritten"),
+        (PathOutcome::Merged, "merged"),
+        (PathOutcome::Copied, "copied"),
+        (PathOutcome::Linked, "linked"),
+        (PathOutcome::Preserved, "preserved"),
+        (PathOutcome::Skipped, "skipped"),
+    ];
+    let mut summary = outcomes
+        .iter()
+        .map(|(outcome, label)| (report.count(*outcome), label))
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, label)| format!("{} {}", count, label))
+        .collect::<Vec<_>>();
+    if summary.is_empty() {
+        summary.push("nothing rendered".to_owned());
+    }
+    let commands = report.commands();
+    if !commands.is_empty() {
+        let failed = commands.iter().filter(|command| !command.succeeded()).count();
+        if failed > 0 {
+            summary.push(format!("{} command(s) run ({} failed)", commands.len(), failed));
+        } else {
+            summary.push(format!("{} command(s) run", commands.len()));
+        }
+    }
+    info!("Done: {}", summary.join(", "));
+}
+
 fn handle_archetect_error(error: ArchetectError) {
     match error {
         ArchetectError::SourceError(error) => handle_source_error(error),
@@ -181,6 +314,13 @@ fn handle_archetect_error(error: ArchetectError) {
         ArchetectError::RenderError(error) => handle_render_error(error),
         ArchetectError::SystemError(error) => handle_system_error(error),
         ArchetectError::CatalogError(error) => handle_catalog_error(error),
+        ArchetectError::MergeError(error) => handle_merge_error(error),
+        ArchetectError::PromptError(error) => handle_prompt_error(error),
+        ArchetectError::MissingAnswers(missing_answers) => handle_missing_answers(missing_answers),
+        ArchetectError::InvalidAnswer { identifier, message } => {
+            error!("Invalid answer for '{}': {}", identifier, message)
+        }
+        ArchetectError::Aborted => error!("Aborted; nothing was rendered."),
         ArchetectError::IoError(error) => handle_io_error(error),
         ArchetectError::AnswerConfigError { source, cause } => handle_answer_config_error(source, cause),
     }
@@ -194,6 +334,46 @@ fn handle_answer_config_error(source: String, error: AnswerConfigError) {
         AnswerConfigError::ParseError(cause) => {
             error!("Error parsing {}: {}", source, cause);
         }
+        AnswerConfigError::WriteError(cause) => {
+            error!("Error writing {}: {}", source, cause);
+        }
+        AnswerConfigError::MissingProfile(profile) => {
+            error!("There is no '{}' answer profile in {}.", profile, source);
+        }
+        AnswerConfigError::CyclicProfile(profile) => {
+            error!("The '{}' answer profile in {} inherits from itself.", profile, source);
+        }
+        AnswerConfigError::MissingAnswer(identifier) => {
+            error!("There is no answer for '{}' in {}.", identifier, source);
+        }
+        AnswerConfigError::ConflictingAnswer(identifier) => {
+            error!(
+                "'{}' in {} answers a field of a variable that is not answered with an object.",
+                identifier, source
+            );
+        }
+        AnswerConfigError::FieldWithoutValue(identifier) => {
+            error!(
+                "'{}' in {} answers a field, and must give a value rather than only a default.",
+                identifier, source
+            );
+        }
+    }
+}
+
+fn handle_merge_error(error: MergeError) {
+    match error {
+        MergeError::UnsupportedFormat(path) => error!(
+            "Unable to determine the merge format of \"{}\".  Specify one of yaml, json or toml.",
+            path.display()
+        ),
+        MergeError::ParseError { path, message } => {
+            error!("Error parsing \"{}\" for merge:\n{}", path.display(), message)
+        }
+        MergeError::SerializeError { path, message } => {
+            error!("Error writing merged \"{}\":\n{}", path.display(), message)
+        }
+        MergeError::IoError(error) => handle_io_error(error),
     }
 }
 
@@ -231,6 +411,16 @@ fn handle_archetype_error(error: ArchetypeError) {
         ArchetypeError::YamlError { path, cause } => {
             error!("Error reading {}:\n{}", path.display(), cause);
         }
+        ArchetypeError::TemplatesNotFound(path) => {
+            error!("Archetype templates directory \"{}\" does not exist", path.display());
+        }
+        ArchetypeError::TemplateError { path, error } => {
+            if let Some(cause) = error.source() {
+                error!("{} in templates \"{}\"", cause, path.display());
+            } else {
+                error!("Error loading templates \"{}\"\n\n{}", path.display(), error);
+            }
+        }
     }
 }
 
@@ -303,6 +493,21 @@ fn handle_render_error(error: RenderError) {
         RenderError::InvalidPathCharacters { source } => {
             error!("Unable read path '{}' as a UTF-8 template", source.display());
         }
+        RenderError::IgnoreFileError { source, message } => {
+            error!("Error reading ignore file '{}':\n{}", source.display(), message);
+        }
+        RenderError::RenderErrors(errors) => {
+            for error in errors {
+                handle_render_error(error);
+            }
+        }
+        RenderError::InvalidSymlink { source, target } => {
+            error!(
+                "Symlink '{}' points to '{}', which is outside of the directory being rendered",
+                source.display(),
+                target.display()
+            );
+        }
     }
 }
 
@@ -314,5 +519,35 @@ fn handle_catalog_error(error: CatalogError) {
         CatalogError::NotFound(error) => error!("Catalog not found: {}", error.to_str().unwrap()),
         CatalogError::IOError(error) => error!("Catalog IO Error: {}", error),
         CatalogError::YamlError(error) => error!("Catalog YAML Read Error: {}", error),
+        CatalogError::PromptError(error) => handle_prompt_error(error),
+    }
+}
+
+fn handle_missing_answers(missing_answers: Vec<MissingAnswer>) {
+    let mut message = format!("Missing answers for {} variable(s):", missing_answers.len());
+    for missing_answer in &missing_answers {
+        message.push_str(&format!(
+            "\n  {} ({}): \"{}\"",
+            missing_answer.identifier(),
+            missing_answer.prompt_type(),
+            missing_answer.prompt()
+        ));
+        if let Some(description) = missing_answer.description() {
+            message.push_str(&format!("\n      {}", description));
+        }
+    }
+    message.push_str("\nSupply them with --answer or --answer-file.");
+    error!("{}", message);
+}
+
+fn handle_prompt_error(error: PromptError) {
+    match error {
+        PromptError::Closed => error!("Input was closed before all questions were answered."),
+        PromptError::Unanswerable { prompt, prompt_type } => {
+            error!("No answer was available for \"{}\" ({}).", prompt, prompt_type)
+        }
+        PromptError::InvalidAnswer { prompt, message } => error!("Invalid answer for \"{}\": {}", prompt, message),
+        PromptError::HelpRequested => error!("Help was asked for where there is none."),
+        PromptError::IoError(error) => handle_io_error(error),
     }
 }
@@ -0,0 +1,47 @@
+use std::fs;
+use std::process::{Command, Stdio};
+
+use indoc::indoc;
+use serde_json::Value;
+
+#[test]
+fn test_json_report_with_printed_output() {
+    let archetype = tempfile::tempdir().unwrap();
+    fs::write(
+        archetype.path().join("archetype.yml"),
+        indoc!(
+            r#"
+            script:
+              - set:
+                  name:
+                    value: Order Service
+              - print: "Rendering {{ name }}"
+              - exec:
+                  command: echo
+                  args: ["{{ name }}"]
+            "#
+        ),
+    )
+    .unwrap();
+    let destination = tempfile::tempdir().unwrap();
+    let home = tempfile::tempdir().unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_archetect"))
+        .env("HOME", home.path())
+        .arg("render")
+        .arg(archetype.path())
+        .arg(destination.path())
+        .args(&["--report", "json"])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["answers"]["name"], Value::from("Order Service"));
+    assert_eq!(report["commands"][0]["command"], Value::from("echo"));
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Rendering Order Service"));
+    assert!(stderr.contains("Order Service\n"));
+}
@@ -6,8 +6,10 @@ use log::{debug, error, info, trace, warn};
 use crate::actions::conditionals::IfAction;
 use crate::actions::exec::ExecAction;
 use crate::actions::foreach::{ForAction, ForEachAction};
+use crate::actions::merge::MergeAction;
 use crate::actions::render::RenderAction;
 use crate::actions::rules::RuleType;
+use crate::actions::scope::ScopeAction;
 use crate::config::{AnswerInfo, VariableInfo};
 use crate::rendering::Renderable;
 use crate::rules::RulesContext;
@@ -18,8 +20,11 @@ pub mod conditionals;
 pub mod exec;
 pub mod foreach;
 pub mod load;
+pub mod merge;
 pub mod render;
+pub mod review;
 pub mod rules;
+pub mod scope;
 pub mod set;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -27,11 +32,13 @@ pub enum ActionId {
     #[serde(rename = "set")]
     Set(LinkedHashMap<String, VariableInfo>),
     #[serde(rename = "scope")]
-    Scope(Vec<ActionId>),
+    Scope(ScopeAction),
     #[serde(rename = "actions")]
     Actions(Vec<ActionId>),
     #[serde(rename = "render")]
     Render(RenderAction),
+    #[serde(rename = "merge")]
+    Merge(MergeAction),
     #[serde(rename = "for-each")]
     ForEach(ForEachAction),
     #[serde(rename = "for")]
@@ -78,12 +85,28 @@ impl ActionId {
             ActionId::Set(variables) => {
                 set::populate_context(archetect, variables, answers, context)?;
             }
+            ActionId::Render(_) if archetect.has_missing_answers() => {
+                debug!("Skipping render action while answers are missing");
+            }
             ActionId::Render(action) => {
                 action.execute(archetect, archetype, destination, rules_context, answers, context)?
             }
+            ActionId::Merge(_) if archetect.has_missing_answers() => {
+                debug!("Skipping merge action while answers are missing");
+            }
+            ActionId::Merge(action) => {
+                action.execute(archetect, archetype, destination, rules_context, answers, context)?
+            }
             ActionId::Actions(action_ids) => {
                 for action_id in action_ids {
-                    action_id.execute(archetect, archetype, destination, rules_context, answers, context)?;
+                    match action_id.execute(archetect, archetype, destination, rules_context, answers, context) {
+                        // While a headless run collects the answers it is missing, actions failing for want
+                        // of them are passed over, so the answers asked for by later actions are reported too.
+                        Err(error) if archetect.headless() && archetect.has_missing_answers() => {
+                            debug!("Skipping a failed action while answers are missing: {:?}", error);
+                        }
+                        result => result?,
+                    }
                     if rules_context.break_triggered() {
                         break;
                     }
@@ -96,20 +119,10 @@ impl ActionId {
             ActionId::LogInfo(message) => info!("{}", message.render(&archetect, context)?),
             ActionId::LogWarn(message) => warn!("{}", message.render(&archetect, context)?),
             ActionId::LogError(message) => error!("{}", message.render(&archetect, context)?),
-            ActionId::Print(message) => println!("{}", message.render(&archetect, context)?),
+            ActionId::Print(message) => archetect.print(message.render(&archetect, context)?),
 
-            ActionId::Scope(actions) => {
-                let mut rules_context = rules_context.clone();
-                let mut scope_context = context.clone();
-                let action: ActionId = actions.into();
-                action.execute(
-                    archetect,
-                    archetype,
-                    destination,
-                    &mut rules_context,
-                    answers,
-                    &mut scope_context,
-                )?;
+            ActionId::Scope(action) => {
+                action.execute(archetect, archetype, destination, rules_context, answers, context)?
             }
             ActionId::If(action) => {
                 action.execute(archetect, archetype, destination, rules_context, answers, context)?
@@ -149,6 +162,9 @@ impl ActionId {
             ActionId::Break => {
                 rules_context.set_break_triggered(true);
             }
+            ActionId::Exec(_) if archetect.has_missing_answers() => {
+                debug!("Skipping exec action while answers are missing");
+            }
             ActionId::Exec(action) => {
                 action.execute(archetect, archetype, destination, rules_context, answers, context)?;
             }
@@ -209,10 +225,52 @@ pub trait Action {
 
 #[cfg(test)]
 mod tests {
-    use crate::actions::render::{ArchetypeOptions, DirectoryOptions};
+    use std::fs;
+    use std::sync::{Arc, Mutex};
+
+    use crate::actions::render::{ArchetypeOptions, DirectoryOptions, FileOptions, TemplateOptions};
+    use crate::events::{Event, EventListener};
+    use crate::util::Source;
 
     use super::*;
 
+    struct RecordingListener(Arc<Mutex<Vec<serde_json::Value>>>);
+
+    impl EventListener for RecordingListener {
+        fn on_event(&self, event: &Event) {
+            self.0.lock().unwrap().push(serde_json::to_value(event).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_print_with_events() {
+        let directory = tempfile::tempdir().unwrap();
+        fs::write(
+            directory.path().join("archetype.yml"),
+            "script:\n  - set:\n      name:\n        value: World\n  - print: \"Hello, {{ name }}!\"\n",
+        )
+        .unwrap();
+        let archetype = Archetype::from_source(&Source::LocalDirectory {
+            path: directory.path().to_owned(),
+        })
+        .unwrap();
+
+        let events = Arc::new(Mutex::new(vec![]));
+        let archetect = Archetect::builder()
+            .with_event_listener(RecordingListener(events.clone()))
+            .build()
+            .unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        archetype
+            .execute_script(&archetect, destination.path(), &LinkedHashMap::new())
+            .unwrap();
+
+        assert!(events
+            .lock()
+            .unwrap()
+            .contains(&serde_json::json!({ "event": "print", "message": "Hello, World!" })));
+    }
+
     #[test]
     fn test_serialize() {
         let actions = vec![
@@ -221,6 +279,8 @@ mod tests {
             ActionId::Render(RenderAction::Archetype(ArchetypeOptions::new(
                 "git@github.com:archetect/archetype-rust-cli.git",
             ))),
+            ActionId::Render(RenderAction::File(FileOptions::new("templates/README.md", "README.md"))),
+            ActionId::Render(RenderAction::Template(TemplateOptions::new("NAME={{ name }}", ".env"))),
         ];
 
         let yaml = serde_yaml::to_string(&actions).unwrap();
@@ -5,7 +5,7 @@ use linked_hash_map::LinkedHashMap;
 use log::trace;
 
 use crate::actions::{Action, ActionId};
-use crate::config::VariableInfo;
+use crate::config::AnswerInfo;
 use crate::rules::RulesContext;
 use crate::template_engine::Context;
 use crate::{Archetect, ArchetectError, Archetype};
@@ -47,6 +47,22 @@ impl IfAction {
     pub fn else_actions(&self) -> Option<&Vec<ActionId>> {
         self.else_actions.as_ref()
     }
+
+    /// Whether every one of the conditions holds, choosing the `then` actions over the `else` actions.
+    pub fn conditions_met<D: AsRef<Path>>(
+        &self,
+        archetect: &Archetect,
+        archetype: &Archetype,
+        destination: D,
+        context: &Context,
+    ) -> Result<bool, ArchetectError> {
+        for condition in &self.conditions {
+            if condition.evaluate(archetect, archetype, destination.as_ref(), context)? == false {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
 }
 
 impl Condition {
@@ -113,18 +129,10 @@ impl Action for IfAction {
         archetype: &Archetype,
         destination: D,
         rules_context: &mut RulesContext,
-        answers: &LinkedHashMap<String, VariableInfo>,
+        answers: &LinkedHashMap<String, AnswerInfo>,
         context: &mut Context,
     ) -> Result<(), ArchetectError> {
-        let mut conditions_are_met = true;
-        for condition in &self.conditions {
-            if condition.evaluate(archetect, archetype, destination.as_ref(), context)? == false {
-                conditions_are_met = false;
-                break;
-            }
-        }
-
-        if conditions_are_met {
+        if self.conditions_met(archetect, archetype, destination.as_ref(), context)? {
             let action: ActionId = self.then_actions().into();
             action.execute(
                 archetect,
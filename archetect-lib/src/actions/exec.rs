@@ -1,12 +1,14 @@
 use std::collections::hash_map::RandomState;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use linked_hash_map::LinkedHashMap;
 use log::{debug, warn};
 
 use crate::actions::Action;
-use crate::config::VariableInfo;
+use crate::config::AnswerInfo;
+use crate::events::Event;
+use crate::report::CommandReport;
 use crate::rules::RulesContext;
 use crate::template_engine::Context;
 use crate::{Archetect, ArchetectError, Archetype};
@@ -81,16 +83,18 @@ impl Action for ExecAction {
         _archetype: &Archetype,
         destination: D,
         _rules_context: &mut RulesContext,
-        _answers: &LinkedHashMap<String, VariableInfo, RandomState>,
+        _answers: &LinkedHashMap<String, AnswerInfo, RandomState>,
         context: &mut Context,
     ) -> Result<(), ArchetectError> {
         let mut command = Command::new(&self.command);
 
-        if let Some(args) = self.args() {
-            for arg in args {
-                command.arg(archetect.render_string(arg, context)?);
+        let mut args = vec![];
+        if let Some(arg_templates) = self.args() {
+            for arg in arg_templates {
+                args.push(archetect.render_string(arg, context)?);
             }
         }
+        command.args(&args);
 
         if let Some(env) = self.env() {
             for (key, value) in env {
@@ -101,30 +105,44 @@ impl Action for ExecAction {
             }
         }
 
-        if let Some(cwd) = &self.cwd {
+        let directory = if let Some(cwd) = &self.cwd {
             if let Ok(cwd) = shellexpand::full(cwd) {
                 let cwd = Path::new(cwd.as_ref());
+                let rendered = archetect.render_string(cwd.display().to_string().as_str(), context)?;
                 if cwd.is_relative() {
-                    command.current_dir(
-                        destination
-                            .as_ref()
-                            .join(archetect.render_string(cwd.display().to_string().as_str(), context)?),
-                    );
+                    Some(destination.as_ref().join(rendered))
                 } else {
-                    command.current_dir(archetect.render_string(cwd.display().to_string().as_str(), context)?);
+                    Some(PathBuf::from(rendered))
                 }
+            } else {
+                None
             }
         } else {
-            command.current_dir(destination);
+            Some(destination.as_ref().to_owned())
+        };
+        if let Some(directory) = &directory {
+            command.current_dir(directory);
+        }
+        // The command's output must not mix with the machine-readable output on stdout.
+        if archetect.reserves_stdout() {
+            command.stdout(std::io::stderr());
         }
 
         debug!("[exec] Executing: {:?}", command);
+        archetect.emit(Event::CommandStarted {
+            command: self.command.clone(),
+            args: args.clone(),
+            directory: directory.clone(),
+        });
+        let report = CommandReport::new(&self.command, args, directory);
         match command.status() {
             Ok(status) => {
                 debug!("[exec] Status: {}", status.code().unwrap());
+                archetect.record_command(report.with_exit_code(status.code()));
             }
             Err(error) => {
                 warn!("[exec] Error: {}", error);
+                archetect.record_command(report.with_error(error.to_string()));
             }
         }
 
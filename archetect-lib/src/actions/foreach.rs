@@ -3,7 +3,7 @@ use std::path::Path;
 use linked_hash_map::LinkedHashMap;
 
 use crate::actions::{Action, ActionId, LoopContext};
-use crate::config::VariableInfo;
+use crate::config::AnswerInfo;
 use crate::rules::RulesContext;
 use crate::template_engine::Context;
 use crate::{Archetect, ArchetectError, Archetype};
@@ -43,7 +43,7 @@ impl Action for ForEachAction {
         archetype: &Archetype,
         destination: D,
         rules_context: &mut RulesContext,
-        answers: &LinkedHashMap<String, VariableInfo>,
+        answers: &LinkedHashMap<String, AnswerInfo>,
         context: &mut Context,
     ) -> Result<(), ArchetectError> {
         match &self.source {
@@ -179,7 +179,7 @@ impl Action for ForAction {
         archetype: &Archetype,
         destination: D,
         rules_context: &mut RulesContext,
-        answers: &LinkedHashMap<String, VariableInfo>,
+        answers: &LinkedHashMap<String, AnswerInfo>,
         context: &mut Context,
     ) -> Result<(), ArchetectError> {
         match &self.options {
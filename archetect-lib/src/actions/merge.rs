@@ -0,0 +1,568 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use linked_hash_map::LinkedHashMap;
+use log::debug;
+use serde_yaml::{Mapping, Value};
+
+use crate::actions::Action;
+use crate::config::AnswerInfo;
+use crate::report::PathOutcome;
+use crate::rules::RulesContext;
+use crate::template_engine::Context;
+use crate::{Archetect, ArchetectError, Archetype};
+
+/// Deep merges a rendered YAML, JSON or TOML fragment into a file of the destination, creating it if it
+/// does not exist. Updating existing files is the point of a merge, so the `overwrite` rule, which only
+/// governs whether rendered files replace existing ones, does not apply: the destination is always written.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MergeAction {
+    #[serde(flatten)]
+    source: MergeSource,
+    into: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<MergeFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    arrays: Option<ArrayStrategy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order: Option<KeyOrder>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum MergeSource {
+    #[serde(rename = "file")]
+    File(String),
+    #[serde(rename = "inline")]
+    Inline(String),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum MergeFormat {
+    #[serde(rename = "yaml")]
+    Yaml,
+    #[serde(rename = "json")]
+    Json,
+    #[serde(rename = "toml")]
+    Toml,
+}
+
+/// How sequences found in both the existing file and the fragment are combined.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ArrayStrategy {
+    /// Fragment items are added after the existing items.
+    #[serde(rename = "append")]
+    Append,
+    /// Fragment items are added after the existing items, unless an equal item is already present.
+    #[serde(rename = "unique")]
+    Unique,
+    /// Fragment items replace the existing items.
+    #[serde(rename = "replace")]
+    Replace,
+}
+
+/// How keys are ordered in the merged result.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum KeyOrder {
+    /// Existing keys keep their position, and new keys follow in the order the fragment declares them.
+    #[serde(rename = "preserve")]
+    Preserve,
+    /// Keys are sorted alphabetically at every level.
+    #[serde(rename = "sort")]
+    Sort,
+}
+
+#[derive(Debug)]
+pub enum MergeError {
+    UnsupportedFormat(PathBuf),
+    ParseError { path: PathBuf, message: String },
+    SerializeError { path: PathBuf, message: String },
+    IoError(std::io::Error),
+}
+
+impl From<std::io::Error> for MergeError {
+    fn from(error: std::io::Error) -> Self {
+        MergeError::IoError(error)
+    }
+}
+
+impl MergeAction {
+    pub fn new<I: Into<String>>(source: MergeSource, into: I) -> MergeAction {
+        MergeAction {
+            source,
+            into: into.into(),
+            format: None,
+            arrays: None,
+            order: None,
+        }
+    }
+
+    pub fn with_format(mut self, format: MergeFormat) -> MergeAction {
+        self.format = Some(format);
+        self
+    }
+
+    pub fn with_arrays(mut self, arrays: ArrayStrategy) -> MergeAction {
+        self.arrays = Some(arrays);
+        self
+    }
+
+    pub fn with_order(mut self, order: KeyOrder) -> MergeAction {
+        self.order = Some(order);
+        self
+    }
+
+    pub fn arrays(&self) -> ArrayStrategy {
+        self.arrays.unwrap_or(ArrayStrategy::Append)
+    }
+
+    pub fn order(&self) -> KeyOrder {
+        self.order.unwrap_or(KeyOrder::Preserve)
+    }
+}
+
+impl MergeFormat {
+    pub fn detect<P: AsRef<Path>>(path: P) -> Option<MergeFormat> {
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("yml") | Some("yaml") => Some(MergeFormat::Yaml),
+            Some("json") => Some(MergeFormat::Json),
+            Some("toml") => Some(MergeFormat::Toml),
+            _ => None,
+        }
+    }
+
+    /// Parses `contents` into an order-preserving value. Every format is read into a
+    /// `serde_yaml::Value`, whose mappings keep their insertion order regardless of the
+    /// `preserve_order` feature.
+    pub fn parse(&self, contents: &str) -> Result<Value, String> {
+        if contents.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        match self {
+            MergeFormat::Yaml => serde_yaml::from_str(contents).map_err(|e| e.to_string()),
+            MergeFormat::Json => serde_json::from_str(contents).map_err(|e| e.to_string()),
+            MergeFormat::Toml => toml::from_str(contents).map_err(|e| e.to_string()),
+        }
+    }
+
+    pub fn serialize(&self, value: &Value) -> Result<String, String> {
+        match self {
+            MergeFormat::Yaml => serde_yaml::to_string(value).map_err(|e| e.to_string()),
+            MergeFormat::Json => serde_json::to_string_pretty(value)
+                .map(|json| json + "\n")
+                .map_err(|e| e.to_string()),
+            MergeFormat::Toml => toml::to_string(&toml_value(value)?).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// The field TOML datetimes are read into, as a mapping of their own holding the datetime as text.
+const TOML_DATETIME_FIELD: &str = "$__toml_private_datetime";
+
+/// Converts a parsed value back to TOML, turning the mappings datetimes were read into back into
+/// datetimes.
+fn toml_value(value: &Value) -> Result<toml::Value, String> {
+    match value {
+        Value::Mapping(mapping) => {
+            if let (1, Some(Value::String(datetime))) = (mapping.len(), mapping.get(&TOML_DATETIME_FIELD.into())) {
+                return datetime.parse().map(toml::Value::Datetime).map_err(|e| e.to_string());
+            }
+            let mut table = toml::value::Table::new();
+            for (key, value) in mapping {
+                let key = match key {
+                    Value::String(key) => key.to_owned(),
+                    key => return Err(format!("TOML keys must be strings, not {:?}", key)),
+                };
+                table.insert(key, toml_value(value)?);
+            }
+            Ok(toml::Value::Table(table))
+        }
+        Value::Sequence(items) => items
+            .iter()
+            .map(toml_value)
+            .collect::<Result<_, _>>()
+            .map(toml::Value::Array),
+        Value::String(value) => Ok(toml::Value::String(value.to_owned())),
+        Value::Bool(value) => Ok(toml::Value::Boolean(*value)),
+        Value::Number(number) => match number.as_i64() {
+            Some(number) => Ok(toml::Value::Integer(number)),
+            None => number
+                .as_f64()
+                .map(toml::Value::Float)
+                .ok_or_else(|| format!("{} is out of range for TOML", number)),
+        },
+        Value::Null => Err("TOML has no null values".to_owned()),
+    }
+}
+
+/// Deep merges `fragment` into `target`. Mappings are merged key by key, sequences are combined
+/// according to `arrays`, and any other value in the fragment replaces the one in the target.
+pub fn merge_values(target: &mut Value, fragment: Value, arrays: ArrayStrategy) {
+    match (target, fragment) {
+        (Value::Mapping(target), Value::Mapping(fragment)) => {
+            for (key, value) in fragment {
+                match target.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value, arrays),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Sequence(target), Value::Sequence(fragment)) => match arrays {
+            ArrayStrategy::Append => target.extend(fragment),
+            ArrayStrategy::Unique => {
+                for item in fragment {
+                    if !target.contains(&item) {
+                        target.push(item);
+                    }
+                }
+            }
+            ArrayStrategy::Replace => *target = fragment,
+        },
+        (target, fragment) => *target = fragment,
+    }
+}
+
+pub fn sort_keys(value: &mut Value) {
+    match value {
+        Value::Mapping(mapping) => {
+            let mut entries: Vec<(Value, Value)> = std::mem::replace(mapping, Mapping::new()).into_iter().collect();
+            entries.sort_by_key(|(key, _)| sort_key(key));
+            for (key, mut value) in entries {
+                sort_keys(&mut value);
+                mapping.insert(key, value);
+            }
+        }
+        Value::Sequence(items) => items.iter_mut().for_each(sort_keys),
+        _ => (),
+    }
+}
+
+fn sort_key(key: &Value) -> String {
+    match key {
+        Value::String(key) => key.to_owned(),
+        key => serde_yaml::to_string(key).unwrap_or_default(),
+    }
+}
+
+impl Action for MergeAction {
+    fn execute<D: AsRef<Path>>(
+        &self,
+        archetect: &Archetect,
+        archetype: &Archetype,
+        destination: D,
+        _rules_context: &mut RulesContext,
+        _answers: &LinkedHashMap<String, AnswerInfo>,
+        context: &mut Context,
+    ) -> Result<(), ArchetectError> {
+        let destination = destination.as_ref().join(archetect.render_string(&self.into, context)?);
+        let format = match self.format.or_else(|| MergeFormat::detect(&destination)) {
+            Some(format) => format,
+            None => return Err(MergeError::UnsupportedFormat(destination).into()),
+        };
+
+        let (fragment_path, fragment) = match &self.source {
+            MergeSource::File(file) => {
+                let path = archetype.path().join(file);
+                let fragment = archetect.render_contents_with(archetype.template_engine(), &path, context)?;
+                (path, fragment)
+            }
+            MergeSource::Inline(inline) => (
+                destination.clone(),
+                archetect.render_string_with(archetype.template_engine(), inline, context)?,
+            ),
+        };
+        let fragment = format.parse(&fragment).map_err(|message| MergeError::ParseError {
+            path: fragment_path,
+            message,
+        })?;
+
+        let (existing, outcome) = if destination.exists() {
+            debug!("Merging     {:?}", destination);
+            let existing = fs::read_to_string(&destination).map_err(MergeError::from)?;
+            (existing, PathOutcome::Merged)
+        } else {
+            debug!("Rendering   {:?}", destination);
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            (String::new(), PathOutcome::Rendered)
+        };
+        let mut merged = format.parse(&existing).map_err(|message| MergeError::ParseError {
+            path: destination.clone(),
+            message,
+        })?;
+
+        merge_values(&mut merged, fragment, self.arrays());
+        if self.order() == KeyOrder::Sort {
+            sort_keys(&mut merged);
+        }
+
+        let mut contents = format
+            .serialize(&merged)
+            .map_err(|message| MergeError::SerializeError {
+                path: destination.clone(),
+                message,
+            })?;
+        // serde_yaml always emits a document marker; only keep it if the file already had one.
+        if format == MergeFormat::Yaml && !existing.trim_start().starts_with("---") {
+            if let Some(stripped) = contents.strip_prefix("---\n") {
+                contents = stripped.to_owned();
+            }
+        }
+        if !contents.ends_with('\n') {
+            contents.push('\n');
+        }
+
+        archetect.write_contents(&destination, &contents)?;
+        archetect.record_path(destination, outcome);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::util::Source;
+
+    fn merge(format: MergeFormat, existing: &str, fragment: &str, arrays: ArrayStrategy) -> String {
+        let mut target = format.parse(existing).unwrap();
+        merge_values(&mut target, format.parse(fragment).unwrap(), arrays);
+        format.serialize(&target).unwrap()
+    }
+
+    #[test]
+    fn test_serialize() {
+        let action = MergeAction::new(
+            MergeSource::File("fragments/services.yml".to_owned()),
+            "docker-compose.yml",
+        )
+        .with_arrays(ArrayStrategy::Unique);
+
+        println!("{}", serde_yaml::to_string(&action).unwrap());
+    }
+
+    #[test]
+    fn test_deserialize_inline() {
+        let yaml = indoc!(
+            r#"
+            ---
+            inline: |
+              members:
+                - "{{ name }}"
+            into: Cargo.toml
+            arrays: unique
+            order: sort
+            "#
+        );
+
+        let action: MergeAction = serde_yaml::from_str(yaml).unwrap();
+        assert!(matches!(action.source, MergeSource::Inline(_)));
+        assert_eq!(action.arrays(), ArrayStrategy::Unique);
+        assert_eq!(action.order(), KeyOrder::Sort);
+        assert_eq!(action.format, None);
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(MergeFormat::detect("docker-compose.yml"), Some(MergeFormat::Yaml));
+        assert_eq!(MergeFormat::detect("config/app.yaml"), Some(MergeFormat::Yaml));
+        assert_eq!(MergeFormat::detect("package.json"), Some(MergeFormat::Json));
+        assert_eq!(MergeFormat::detect("Cargo.toml"), Some(MergeFormat::Toml));
+        assert_eq!(MergeFormat::detect("README.md"), None);
+    }
+
+    #[test]
+    fn test_deep_merge_yaml_preserves_order() {
+        let existing = indoc!(
+            r#"
+            version: "3"
+            services:
+              web:
+                image: nginx
+            "#
+        );
+        let fragment = indoc!(
+            r#"
+            services:
+              db:
+                image: postgres
+              web:
+                ports:
+                  - "80:80"
+            "#
+        );
+
+        assert_eq!(
+            merge(MergeFormat::Yaml, existing, fragment, ArrayStrategy::Append),
+            indoc!(
+                r#"
+                ---
+                version: "3"
+                services:
+                  web:
+                    image: nginx
+                    ports:
+                      - "80:80"
+                  db:
+                    image: postgres
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn test_json_preserves_order() {
+        let existing = r#"{"name": "app", "dependencies": {"react": "^16.0.0"}, "private": true}"#;
+        let fragment = r#"{"dependencies": {"axios": "^0.19.0"}}"#;
+
+        let mut merged = MergeFormat::Json.parse(existing).unwrap();
+        merge_values(
+            &mut merged,
+            MergeFormat::Json.parse(fragment).unwrap(),
+            ArrayStrategy::Append,
+        );
+        assert_eq!(
+            serde_json::to_string(&merged).unwrap(),
+            r#"{"name":"app","dependencies":{"react":"^16.0.0","axios":"^0.19.0"},"private":true}"#
+        );
+    }
+
+    #[test]
+    fn test_array_strategies() {
+        let existing = "[a, b]";
+        let fragment = "[b, c]";
+
+        assert_eq!(
+            MergeFormat::Yaml.parse(&merge(MergeFormat::Yaml, existing, fragment, ArrayStrategy::Append)),
+            MergeFormat::Yaml.parse("[a, b, b, c]")
+        );
+        assert_eq!(
+            MergeFormat::Yaml.parse(&merge(MergeFormat::Yaml, existing, fragment, ArrayStrategy::Unique)),
+            MergeFormat::Yaml.parse("[a, b, c]")
+        );
+        assert_eq!(
+            MergeFormat::Yaml.parse(&merge(MergeFormat::Yaml, existing, fragment, ArrayStrategy::Replace)),
+            MergeFormat::Yaml.parse("[b, c]")
+        );
+    }
+
+    #[test]
+    fn test_merge_toml() {
+        let existing = indoc!(
+            r#"
+            [workspace]
+            members = ["core"]
+
+            [profile.release]
+            lto = true
+            "#
+        );
+        let fragment = indoc!(
+            r#"
+            [workspace]
+            members = ["core", "cli"]
+            "#
+        );
+
+        assert_eq!(
+            merge(MergeFormat::Toml, existing, fragment, ArrayStrategy::Unique),
+            indoc!(
+                r#"
+                [workspace]
+                members = ["core", "cli"]
+                [profile.release]
+                lto = true
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn test_merge_toml_datetimes() {
+        let existing = indoc!(
+            r#"
+            released = 1979-05-27T07:32:00Z
+
+            [schedule]
+            start = 1979-05-27
+            "#
+        );
+        let fragment = indoc!(
+            r#"
+            [schedule]
+            end = 07:32:00
+            "#
+        );
+
+        assert_eq!(
+            merge(MergeFormat::Toml, existing, fragment, ArrayStrategy::Append),
+            indoc!(
+                r#"
+                released = 1979-05-27T07:32:00Z
+
+                [schedule]
+                start = 1979-05-27
+                end = 07:32:00
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn test_execute_inline_with_archetype_templates() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path();
+        fs::create_dir_all(root.join("templates/partials")).unwrap();
+        fs::write(root.join("templates/partials/db.yml"), "{ image: postgres }").unwrap();
+        fs::write(
+            root.join("archetype.yml"),
+            indoc!(
+                r#"
+                templates: templates
+                script:
+                  - merge:
+                      inline: |
+                        services:
+                          db: {% include "partials/db.yml" %}
+                      into: docker-compose.yml
+                "#
+            ),
+        )
+        .unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        fs::write(
+            destination.path().join("docker-compose.yml"),
+            "services:\n  web:\n    image: nginx\n",
+        )
+        .unwrap();
+
+        let archetect = Archetect::build().unwrap();
+        let archetype = Archetype::from_source(&Source::LocalDirectory { path: root.to_owned() }).unwrap();
+        archetype
+            .execute_script(&archetect, destination.path(), &LinkedHashMap::new())
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(destination.path().join("docker-compose.yml")).unwrap(),
+            "services:\n  web:\n    image: nginx\n  db:\n    image: postgres\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_into_empty() {
+        assert_eq!(
+            merge(MergeFormat::Json, "", r#"{"b": 1, "a": 2}"#, ArrayStrategy::Append),
+            "{\n  \"b\": 1,\n  \"a\": 2\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let mut value = MergeFormat::Yaml.parse("{b: {d: 1, c: 2}, a: 3}").unwrap();
+        sort_keys(&mut value);
+        assert_eq!(serde_json::to_string(&value).unwrap(), r#"{"a":3,"b":{"c":2,"d":1}}"#);
+    }
+}
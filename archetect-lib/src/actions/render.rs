@@ -15,6 +15,11 @@ pub enum RenderAction {
     Directory(DirectoryOptions),
     #[serde(rename = "archetype")]
     Archetype(ArchetypeOptions),
+    #[serde(rename = "file")]
+    File(FileOptions),
+    /// An inline template, written flat as `template: "<text>"` alongside its `to:` path.
+    #[serde(untagged)]
+    Template(TemplateOptions),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -24,6 +29,19 @@ pub struct DirectoryOptions {
     source: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileOptions {
+    source: String,
+    destination: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateOptions {
+    template: String,
+    to: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ArchetypeOptions {
     #[serde(skip_serializing_if = "Option::is_none", rename = "answers-include")]
@@ -49,6 +67,24 @@ impl DirectoryOptions {
     }
 }
 
+impl FileOptions {
+    pub fn new<S: Into<String>, D: Into<String>>(source: S, destination: D) -> FileOptions {
+        FileOptions {
+            source: source.into(),
+            destination: destination.into(),
+        }
+    }
+}
+
+impl TemplateOptions {
+    pub fn new<C: Into<String>, T: Into<String>>(template: C, to: T) -> TemplateOptions {
+        TemplateOptions {
+            template: template.into(),
+            to: to.into(),
+        }
+    }
+}
+
 impl ArchetypeOptions {
     pub fn new<S: Into<String>>(source: S) -> ArchetypeOptions {
         ArchetypeOptions {
@@ -88,7 +124,35 @@ impl Action for RenderAction {
                     destination.as_ref().to_owned()
                 };
                 fs::create_dir_all(destination.as_path())?;
-                archetect.render_directory(context, source, destination, rules_context)?;
+                archetect.render_directory_within(
+                    archetype.template_engine(),
+                    archetype.path(),
+                    context,
+                    source,
+                    destination,
+                    rules_context,
+                )?;
+            }
+
+            RenderAction::File(options) => {
+                let source = archetype.path().join(&options.source);
+                let destination = destination
+                    .as_ref()
+                    .join(archetect.render_string(&options.destination, context)?);
+                archetect.render_file_with(archetype.template_engine(), context, source, destination, rules_context)?;
+            }
+
+            RenderAction::Template(options) => {
+                let destination = destination
+                    .as_ref()
+                    .join(archetect.render_string(&options.to, context)?);
+                archetect.render_template_with(
+                    archetype.template_engine(),
+                    context,
+                    &options.template,
+                    destination,
+                    rules_context,
+                )?;
             }
 
             RenderAction::Archetype(options) => {
@@ -104,9 +168,7 @@ impl Action for RenderAction {
                 if let Some(answers_include) = &options.answers_include {
                     for identifier in answers_include {
                         if let Some(value) = context.get(identifier) {
-                            if let Some(string) = value.as_str() {
-                                scoped_answers.insert(identifier.to_owned(), AnswerInfo::with_value(string).build());
-                            }
+                            scoped_answers.insert(identifier.to_owned(), AnswerInfo::with_value(value.clone()).build());
                         }
                     }
                 }
@@ -128,3 +190,57 @@ impl Action for RenderAction {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use crate::actions::ActionId;
+
+    use super::*;
+
+    #[test]
+    fn test_deserialize_render_file() {
+        let actions: Vec<ActionId> = serde_yaml::from_str(indoc!(
+            r#"
+            - render:
+                file:
+                  source: templates/README.md
+                  destination: "{{ name }}/README.md"
+            "#
+        ))
+        .unwrap();
+
+        match &actions[0] {
+            ActionId::Render(RenderAction::File(options)) => {
+                assert_eq!(options.source, "templates/README.md");
+                assert_eq!(options.destination, "{{ name }}/README.md");
+            }
+            action => panic!("Unexpected action: {:?}", action),
+        }
+    }
+
+    #[test]
+    fn test_deserialize_render_template() {
+        let actions: Vec<ActionId> = serde_yaml::from_str(indoc!(
+            r#"
+            - render:
+                template: "NAME={{ name }}"
+                to: .env
+            "#
+        ))
+        .unwrap();
+
+        match &actions[0] {
+            ActionId::Render(RenderAction::Template(options)) => {
+                assert_eq!(options.template, "NAME={{ name }}");
+                assert_eq!(options.to, ".env");
+            }
+            action => panic!("Unexpected action: {:?}", action),
+        }
+
+        let yaml = serde_yaml::to_string(&actions).unwrap();
+        let round_trip: Vec<ActionId> = serde_yaml::from_str(&yaml).unwrap();
+        assert!(matches!(&round_trip[0], ActionId::Render(RenderAction::Template(_))));
+    }
+}
@@ -0,0 +1,234 @@
+use std::path::Path;
+
+use linked_hash_map::LinkedHashMap;
+use log::warn;
+use serde_json::Value;
+
+use crate::actions::{set, ActionId};
+use crate::config::{AnswerInfo, VariableInfo, VariableType};
+use crate::rules::RulesContext;
+use crate::template_engine::Context;
+use crate::{Archetect, ArchetectError, Archetype};
+
+/// Runs `actions`, stopping once the last of its `set` actions is done to show the answers given so
+/// far. Each may be changed, which runs those actions again so that values derived from it follow,
+/// before the answers are accepted and the remaining actions run, or the run is abandoned.
+///
+/// Abandoning a run must leave the destination untouched, so the review comes before the first action
+/// that renders, merges or executes anything, and answers set after it are taken as they are given.
+/// Answers set within `if` actions and nested action lists are reviewed; those set within scopes and
+/// loops are not.
+pub fn execute_with_review(
+    archetect: &Archetect,
+    archetype: &Archetype,
+    destination: &Path,
+    rules_context: &mut RulesContext,
+    answers: &LinkedHashMap<String, AnswerInfo>,
+    context: &mut Context,
+    actions: &[ActionId],
+) -> Result<(), ArchetectError> {
+    let first_write = actions.iter().position(writes).unwrap_or(actions.len());
+    let split = actions[..first_write]
+        .iter()
+        .rposition(|action| !sets(action).is_empty())
+        .map_or(0, |index| index + 1);
+    if actions[split..].iter().any(|action| !sets(action).is_empty()) {
+        warn!("Answers set after the script first renders, merges or executes are not reviewed");
+    }
+    if split == 0 {
+        return ActionId::from(actions).execute(archetect, archetype, destination, rules_context, answers, context);
+    }
+    let (prompts, rest) = actions.split_at(split);
+
+    let mut given = prompt(
+        archetect,
+        archetype,
+        destination,
+        rules_context,
+        answers,
+        context,
+        prompts,
+    )?;
+    if rules_context.break_triggered() {
+        return Ok(());
+    }
+
+    // A variable may be set in both branches of an `if`, and is reviewed if either one prompts for it.
+    let mut variables = LinkedHashMap::new();
+    for set_variables in prompts.iter().flat_map(sets) {
+        for (identifier, variable_info) in set_variables {
+            let reviewed = variables.entry(identifier.to_owned()).or_insert(variable_info);
+            if reviewed.has_derived_value() {
+                *reviewed = variable_info;
+            }
+        }
+    }
+
+    loop {
+        let reviewed = variables
+            .iter()
+            .filter(|(_, variable_info)| !variable_info.has_derived_value())
+            .filter_map(|(identifier, variable_info)| {
+                context
+                    .get(identifier)
+                    .map(|value| (identifier.to_owned(), display_value(variable_info, value)))
+            })
+            .collect::<Vec<_>>();
+        if reviewed.is_empty() {
+            break;
+        }
+
+        let width = reviewed
+            .iter()
+            .map(|(identifier, _)| identifier.len())
+            .max()
+            .unwrap_or_default();
+        let mut options = reviewed
+            .iter()
+            .map(|(identifier, value)| format!("{:width$}  {}", identifier, value, width = width))
+            .collect::<Vec<_>>();
+        options.push("Accept these answers".to_owned());
+        options.push("Abort".to_owned());
+
+        let choice = archetect
+            .prompter()
+            .select("Choose an answer to change, or accept or abort:", &options)?;
+        if choice == reviewed.len() {
+            break;
+        }
+        let selected = match reviewed.get(choice) {
+            Some((identifier, _)) => identifier,
+            None => return Err(ArchetectError::Aborted),
+        };
+
+        // Run the actions again with every answer given so far, except the one being changed, which is
+        // asked for again with its current answer as the default.
+        let mut answers = answers.clone();
+        for (identifier, value) in &given {
+            answers.insert(identifier.to_owned(), AnswerInfo::with_value(value.clone()).build());
+        }
+        let mut changed = AnswerInfo::new();
+        if variables[selected].variable_type() != VariableType::Secret {
+            if let Some(value) = answers.get(selected).and_then(|answer| answer.value()) {
+                changed = changed.with_default(value.clone());
+            }
+        }
+        answers.insert(selected.to_owned(), changed.build());
+
+        for identifier in variables.keys() {
+            context.remove(identifier);
+        }
+        given.extend(prompt(
+            archetect,
+            archetype,
+            destination,
+            rules_context,
+            &answers,
+            context,
+            prompts,
+        )?);
+        if rules_context.break_triggered() {
+            return Ok(());
+        }
+    }
+
+    ActionId::from(rest).execute(archetect, archetype, destination, rules_context, answers, context)
+}
+
+/// Runs `actions`, returning the values given at the prompts of their `set` actions, including those
+/// within `if` actions and nested action lists.
+fn prompt(
+    archetect: &Archetect,
+    archetype: &Archetype,
+    destination: &Path,
+    rules_context: &mut RulesContext,
+    answers: &LinkedHashMap<String, AnswerInfo>,
+    context: &mut Context,
+    actions: &[ActionId],
+) -> Result<LinkedHashMap<String, Value>, ArchetectError> {
+    let mut given = LinkedHashMap::new();
+    for action in actions {
+        match action {
+            ActionId::Set(variables) => given.extend(set::populate_context(archetect, variables, answers, context)?),
+            ActionId::Actions(actions) => given.extend(prompt(
+                archetect,
+                archetype,
+                destination,
+                rules_context,
+                answers,
+                context,
+                actions,
+            )?),
+            ActionId::If(action) => {
+                let actions = if action.conditions_met(archetect, archetype, destination, context)? {
+                    Some(action.then_actions())
+                } else {
+                    action.else_actions()
+                };
+                if let Some(actions) = actions {
+                    given.extend(prompt(
+                        archetect,
+                        archetype,
+                        destination,
+                        rules_context,
+                        answers,
+                        context,
+                        actions,
+                    )?);
+                }
+            }
+            action => action.execute(archetect, archetype, destination, rules_context, answers, context)?,
+        }
+        if rules_context.break_triggered() {
+            break;
+        }
+    }
+    Ok(given)
+}
+
+/// The variables of the `set` actions that `action` runs which can be reviewed: its own, if it is one,
+/// or those within `if` actions and nested action lists.
+fn sets(action: &ActionId) -> Vec<&LinkedHashMap<String, VariableInfo>> {
+    match action {
+        ActionId::Set(variables) => vec![variables],
+        ActionId::Actions(actions) => actions.iter().flat_map(sets).collect(),
+        ActionId::If(action) => action
+            .then_actions()
+            .iter()
+            .chain(action.else_actions().into_iter().flatten())
+            .flat_map(sets)
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Whether running `action` may write to the destination, or run commands that might.
+fn writes(action: &ActionId) -> bool {
+    match action {
+        ActionId::Render(_) | ActionId::Merge(_) | ActionId::Exec(_) => true,
+        ActionId::Scope(action) => action.actions().iter().any(writes),
+        ActionId::Actions(actions) | ActionId::Loop(actions) => actions.iter().any(writes),
+        ActionId::If(action) => action
+            .then_actions()
+            .iter()
+            .chain(action.else_actions().into_iter().flatten())
+            .any(writes),
+        ActionId::ForEach(action) => action.actions().iter().any(writes),
+        ActionId::For(action) => action.actions().iter().any(writes),
+        _ => false,
+    }
+}
+
+/// The value of a variable as shown for review: text as it is, versions by their number, secrets
+/// hidden, and everything else in its JSON form.
+fn display_value(variable_info: &VariableInfo, value: &Value) -> String {
+    match (variable_info.variable_type(), value) {
+        (VariableType::Secret, _) => "********".to_owned(),
+        (_, Value::String(value)) => value.to_owned(),
+        (VariableType::Semver, Value::Object(fields)) => match fields.get("version") {
+            Some(Value::String(version)) => version.to_owned(),
+            _ => value.to_string(),
+        },
+        (_, value) => value.to_string(),
+    }
+}
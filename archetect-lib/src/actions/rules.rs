@@ -1,5 +1,5 @@
 use crate::actions::Action;
-use crate::config::{RuleConfig, VariableInfo};
+use crate::config::{AnswerInfo, RuleConfig};
 use crate::rules::RulesContext;
 use crate::template_engine::Context;
 use crate::{Archetect, ArchetectError, Archetype};
@@ -16,7 +16,10 @@ pub enum RuleType {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DestinationOptions {
-    overwrite: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    overwrite: Option<bool>,
+    #[serde(rename = "skip-empty-files", skip_serializing_if = "Option::is_none")]
+    skip_empty_files: Option<bool>,
 }
 
 impl Action for RuleType {
@@ -26,7 +29,7 @@ impl Action for RuleType {
         _archetype: &Archetype,
         _destination: D,
         rules_context: &mut RulesContext,
-        _answers: &LinkedHashMap<String, VariableInfo>,
+        _answers: &LinkedHashMap<String, AnswerInfo>,
         _context: &mut Context,
     ) -> Result<(), ArchetectError> {
         match self {
@@ -34,7 +37,12 @@ impl Action for RuleType {
                 rules_context.insert_path_rules(rules);
             }
             RuleType::DestinationRules(options) => {
-                rules_context.set_overwrite(options.overwrite);
+                if let Some(overwrite) = options.overwrite {
+                    rules_context.set_overwrite(overwrite);
+                }
+                if let Some(skip_empty_files) = options.skip_empty_files {
+                    rules_context.set_skip_empty_files(skip_empty_files);
+                }
             }
         }
         Ok(())
@@ -0,0 +1,172 @@
+use std::path::Path;
+
+use linked_hash_map::LinkedHashMap;
+use log::debug;
+
+use crate::actions::{Action, ActionId};
+use crate::config::AnswerInfo;
+use crate::rules::RulesContext;
+use crate::template_engine::Context;
+use crate::{Archetect, ArchetectError, Archetype};
+
+/// Actions run against copies of the context and rules, so that nothing set within them outlives the
+/// scope. Given as a list of actions, or with the variables to `export` back to the enclosing context.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum ScopeAction {
+    Actions(Vec<ActionId>),
+    Exporting {
+        actions: Vec<ActionId>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        export: Vec<String>,
+    },
+}
+
+impl ScopeAction {
+    pub fn new(actions: Vec<ActionId>) -> ScopeAction {
+        ScopeAction::Actions(actions)
+    }
+
+    pub fn with_export<E: Into<String>>(self, identifier: E) -> ScopeAction {
+        let (actions, mut export) = match self {
+            ScopeAction::Actions(actions) => (actions, vec![]),
+            ScopeAction::Exporting { actions, export } => (actions, export),
+        };
+        export.push(identifier.into());
+        ScopeAction::Exporting { actions, export }
+    }
+
+    pub fn actions(&self) -> &[ActionId] {
+        match self {
+            ScopeAction::Actions(actions) | ScopeAction::Exporting { actions, .. } => actions,
+        }
+    }
+
+    /// The variables copied back to the enclosing context once the scope finishes.
+    pub fn export(&self) -> &[String] {
+        match self {
+            ScopeAction::Actions(_) => &[],
+            ScopeAction::Exporting { export, .. } => export,
+        }
+    }
+}
+
+impl Action for ScopeAction {
+    fn execute<D: AsRef<Path>>(
+        &self,
+        archetect: &Archetect,
+        archetype: &Archetype,
+        destination: D,
+        rules_context: &mut RulesContext,
+        answers: &LinkedHashMap<String, AnswerInfo>,
+        context: &mut Context,
+    ) -> Result<(), ArchetectError> {
+        let mut rules_context = rules_context.clone();
+        let mut scope_context = context.clone();
+        let action: ActionId = self.actions().into();
+
+        // The answers given within the scope are reported separately, so that only those exported reach
+        // the report of the run.
+        let in_run = archetect.in_run();
+        if in_run {
+            archetect.begin_report();
+        }
+        let result = action.execute(
+            archetect,
+            archetype,
+            destination,
+            &mut rules_context,
+            answers,
+            &mut scope_context,
+        );
+        let report = if in_run { Some(archetect.end_report()) } else { None };
+        result?;
+
+        // Variables the scope never set leave the enclosing context as it was.
+        for identifier in self.export() {
+            match scope_context.get(identifier) {
+                Some(value) => context.insert(identifier.as_str(), value),
+                None => debug!("Not exporting '{}', as the scope did not set it", identifier),
+            }
+            if let Some(value) = report.as_ref().and_then(|report| report.answers().get(identifier)) {
+                archetect.record_answer(identifier.as_str(), value.clone());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use indoc::indoc;
+    use linked_hash_map::LinkedHashMap;
+
+    use crate::actions::scope::ScopeAction;
+    use crate::util::Source;
+    use crate::{Archetect, Archetype};
+
+    #[test]
+    fn test_deserialize() {
+        let scope: ScopeAction = serde_yaml::from_str("- print: hello").unwrap();
+        assert_eq!(scope.actions().len(), 1);
+        assert!(scope.export().is_empty());
+
+        let scope: ScopeAction = serde_yaml::from_str(indoc!(
+            r#"
+            actions:
+              - print: hello
+            export: [greeting]
+            "#
+        ))
+        .unwrap();
+        assert_eq!(scope.actions().len(), 1);
+        assert_eq!(scope.export(), &["greeting".to_owned()]);
+    }
+
+    #[test]
+    fn test_execute_export() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path();
+        fs::write(
+            root.join("archetype.yml"),
+            indoc!(
+                r#"
+                script:
+                  - scope:
+                      actions:
+                        - set:
+                            first:
+                              value: Order
+                            full:
+                              value: "{{ first }} Service"
+                      export: [full, missing]
+                  - set:
+                      summary:
+                        value: "{{ full }}{% if first is defined %} ({{ first }}){% endif %}"
+                "#
+            ),
+        )
+        .unwrap();
+
+        let archetect = Archetect::build().unwrap();
+        let archetype = Archetype::from_source(&Source::LocalDirectory { path: root.to_owned() }).unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let report = archetype
+            .execute_script(&archetect, destination.path(), &LinkedHashMap::new())
+            .unwrap();
+
+        // `first` was not exported, so it is undefined once the scope finishes and left out of the summary.
+        assert_eq!(
+            report.answers().get("summary"),
+            Some(&serde_json::Value::from("Order Service"))
+        );
+        assert_eq!(
+            report.answers().get("full"),
+            Some(&serde_json::Value::from("Order Service"))
+        );
+        assert!(report.answers().get("first").is_none());
+        assert!(report.answers().get("missing").is_none());
+    }
+}
@@ -1,96 +1,145 @@
-use std::collections::{HashMap, HashSet};
-
 use linked_hash_map::LinkedHashMap;
-use read_input::prelude::*;
-use serde_json::Value;
+use semver::{Identifier, Version};
+use serde_json::{Map, Value};
 
 use crate::config::{AnswerInfo, VariableInfo, VariableType};
+use crate::input::{parse_bool, parse_list, MissingAnswer, PromptError};
 use crate::template_engine::Context;
-use crate::{Archetect, ArchetectError};
+use crate::{Archetect, ArchetectError, RenderError};
+
+/// The value of a variable as it may be reported: `None` for secrets, and objects without their secret
+/// fields, however deeply they are nested.
+fn without_secrets(variable_info: &VariableInfo, value: &Value) -> Option<Value> {
+    match (variable_info.variable_type(), value) {
+        (VariableType::Secret, _) => None,
+        (VariableType::Object(schema), Value::Object(fields)) => Some(object_without_secrets(&schema, fields)),
+        (VariableType::ObjectList(schema), Value::Array(items)) => Some(Value::Array(
+            items
+                .iter()
+                .map(|item| match item {
+                    Value::Object(fields) => object_without_secrets(&schema, fields),
+                    item => item.clone(),
+                })
+                .collect(),
+        )),
+        (_, value) => Some(value.clone()),
+    }
+}
 
-const ACCEPTABLE_BOOLEANS: [&str; 8] = ["y", "yes", "true", "t", "n", "no", "false", "f"];
+fn object_without_secrets(schema: &LinkedHashMap<String, VariableInfo>, fields: &Map<String, Value>) -> Value {
+    Value::Object(
+        fields
+            .iter()
+            .filter_map(|(field, value)| match schema.get(field) {
+                Some(field_info) => without_secrets(field_info, value).map(|value| (field.to_owned(), value)),
+                None => Some((field.to_owned(), value.clone())),
+            })
+            .collect(),
+    )
+}
 
+/// Sets each of `variables` in `context`, returning the values given at prompts as they were entered,
+/// before any formatting.
 pub fn populate_context(
     archetect: &Archetect,
     variables: &LinkedHashMap<String, VariableInfo>,
     answers: &LinkedHashMap<String, AnswerInfo>,
     context: &mut Context,
-) -> Result<(), ArchetectError> {
+) -> Result<LinkedHashMap<String, Value>, ArchetectError> {
+    let prompted = populate_variables(archetect, variables, answers, context, "")?;
+
     for (identifier, variable_info) in variables {
-        // 1) If there is an answer for this variable, and has an explicit value, use that first.
-        if let Some(answer) = answers.get(identifier) {
-            let mut answer_satisfied = false;
-
-            if let Some(value) = answer.value() {
-                match variable_info.variable_type() {
-                    VariableType::Enum(options) => {
-                        if options.contains(&value.to_owned()) {
-                            context.insert(identifier.as_str(), &archetect.render_string(value, context)?);
-                            answer_satisfied = true;
-                        }
-                    }
-                    VariableType::Bool => {
-                        let value = value.to_lowercase();
-                        if ACCEPTABLE_BOOLEANS.contains(&value.as_str()) {
-                            let value = match ACCEPTABLE_BOOLEANS.iter().position(|i| i == &value.as_str()).unwrap() {
-                                0..=3 => true,
-                                _ => false,
-                            };
-                            context.insert(identifier.as_str(), &value);
-                            answer_satisfied = true;
-                        }
-                    }
-                    VariableType::Int => {
-                        if let Ok(value) = value.parse::<i64>() {
-                            context.insert(identifier.as_str(), &value);
-                            answer_satisfied = true;
-                        }
-                    }
-                    VariableType::String => {
-                        context.insert(identifier.as_str(), &archetect.render_string(value, context)?);
-                        answer_satisfied = true;
-                    }
-                    VariableType::Array => {
-                        if let Some(variable_value) = variable_info.value() {
-                            let mut temp_context = context.clone();
-                            temp_context.insert("item", value);
+        if let Some(value) = context
+            .get(identifier)
+            .and_then(|value| without_secrets(variable_info, value))
+        {
+            archetect.record_answer(identifier.as_str(), value);
+        }
+    }
+
+    Ok(prompted)
+}
+
+/// Sets each of `variables` in `context`. `path` qualifies the names of nested variables, such as
+/// `entities[0].`, when reporting those left without an answer. Returns the values given at prompts.
+fn populate_variables(
+    archetect: &Archetect,
+    variables: &LinkedHashMap<String, VariableInfo>,
+    answers: &LinkedHashMap<String, AnswerInfo>,
+    context: &mut Context,
+    path: &str,
+) -> Result<LinkedHashMap<String, Value>, ArchetectError> {
+    let mut prompted = LinkedHashMap::new();
+    for (identifier, variable_info) in variables {
+        let qualified = format!("{}{}", path, identifier);
+
+        // Skip variables whose condition is not met, giving them their fallback, if any.
+        // Variables whose condition depends on a missing answer are skipped altogether.
+        if let Some(condition) = variable_info.when() {
+            match unless_missing(archetect, archetect.evaluate_condition(condition, context))? {
+                Some(true) => (),
+                Some(false) => {
+                    if let Some(fallback) = variable_info.fallback() {
+                        if let Some(fallback) = unless_missing(archetect, archetect.render_string(fallback, context))? {
                             context.insert(
                                 identifier.as_str(),
-                                &archetect.render_string(variable_value, &temp_context)?,
+                                &fallback_value(&variable_info.variable_type(), fallback),
                             );
-                            answer_satisfied = true;
-                        } else {
-                            context.insert(identifier.as_str(), &archetect.render_string(value, context)?);
                         }
                     }
+                    continue;
                 }
+                None => continue,
             }
+        }
 
-            if answer_satisfied {
-                // Allow answered variables to be formatted or derived
-                if let Some(value) = variable_info.value() {
-                    match variable_info.variable_type() {
-                        // Special handling for lists
-                        VariableType::Array => {}
-                        _ => {
-                            context.insert(identifier.as_str(), &archetect.render_string(value, context)?);
+        // 1) If there is an answer for this variable, and has an explicit value, use that first.
+        if let Some(value) = answers.get(identifier).and_then(|answer| answer.value()) {
+            let value = answer_value(archetect, context, &qualified, variable_info, value)?;
+            variable_info
+                .validate(&value)
+                .map_err(|message| ArchetectError::InvalidAnswer {
+                    identifier: identifier.to_owned(),
+                    message,
+                })?;
+
+            match (variable_info.variable_type(), value) {
+                (VariableType::Array, Value::Array(items)) => {
+                    let items = render_list_items(archetect, context, items, variable_info)?;
+                    context.insert(identifier.as_str(), &items);
+                }
+                (VariableType::MultiEnum(_), value)
+                | (VariableType::Object(_), value)
+                | (VariableType::ObjectList(_), value) => {
+                    context.insert(identifier.as_str(), &value);
+                }
+                (variable_type, value) => {
+                    context.insert(identifier.as_str(), &typed_value(&variable_type, value));
+
+                    // Allow answered variables to be formatted or derived
+                    if let Some(value) = variable_info.value() {
+                        if let Some(value) = unless_missing(archetect, archetect.render_string(value, context))? {
+                            context.insert(identifier.as_str(), &value);
                         }
                     }
                 }
-                continue;
             }
+            continue;
         }
 
         // Insert wholly derived values
         if variable_info.has_derived_value() {
             if let Some(value) = variable_info.value() {
-                context.insert(identifier.as_str(), &archetect.render_string(value, context)?);
+                if let Some(value) = unless_missing(archetect, archetect.render_string(value, context))? {
+                    context.insert(identifier.as_str(), &value);
+                }
                 continue;
             }
         }
 
-        let mut prompt = if let Some(prompt) = variable_info.prompt() {
-            format!("{} ", archetect.render_string(prompt.trim(), context)?)
+        let prompt = if let Some(prompt) = variable_info.prompt() {
+            let rendered = unless_missing(archetect, archetect.render_string(prompt.trim(), context))?;
+            format!("{} ", rendered.unwrap_or_else(|| prompt.trim().to_owned()))
         } else {
             format!("{}: ", identifier)
         };
@@ -98,196 +147,432 @@ pub fn populate_context(
         // Determine if a default can be provided.
         let default = if let Some(answer) = answers.get(identifier) {
             if let Some(default) = answer.default() {
-                Some(archetect.render_string(default, context)?)
+                unless_missing(archetect, archetect.render_string(&answer_text(default), context))?
             } else if let Some(default) = variable_info.default() {
-                Some(archetect.render_string(default, context)?)
+                unless_missing(archetect, archetect.render_string(default, context))?
             } else {
                 None
             }
         } else if let Some(default) = variable_info.default() {
-            Some(archetect.render_string(default, context)?)
+            unless_missing(archetect, archetect.render_string(default, context))?
         } else {
             None
         };
 
-        let value = match variable_info.variable_type() {
-            VariableType::Enum(values) => prompt_for_enum(&mut prompt, &values, &default),
-            VariableType::Bool => prompt_for_bool(&mut prompt, &default),
-            VariableType::Int => prompt_for_int(&mut prompt, &default),
-            VariableType::Array => prompt_for_list(archetect, context, &prompt, variable_info)?,
-            VariableType::String => prompt_for_string(&mut prompt, &default, variable_info.required()),
+        let value = match prompt_for_value(archetect, context, &qualified, &prompt, default, variable_info) {
+            // Headless runs carry on without the value, so every missing answer can be reported at once.
+            Err(ArchetectError::PromptError(PromptError::Unanswerable { prompt, prompt_type }))
+                if archetect.headless() =>
+            {
+                let mut missing_answer = MissingAnswer::new(qualified, prompt.trim(), prompt_type);
+                if let Some(description) = variable_info.description() {
+                    let rendered = archetect.render_string(description, context);
+                    missing_answer =
+                        missing_answer.with_description(rendered.unwrap_or_else(|_| description.to_owned()));
+                }
+                archetect.record_missing_answer(missing_answer);
+                continue;
+            }
+            result => result?,
         };
+        prompted.insert(identifier.to_owned(), value.clone());
 
-        if let Some(value) = value {
-            context.insert(identifier, &value);
+        match value {
+            Value::Array(items) => {
+                let items = render_list_items(archetect, context, items, variable_info)?;
+                context.insert(identifier, &items);
+            }
+            value => {
+                context.insert(identifier, &typed_value(&variable_info.variable_type(), value));
 
-            // Allow prompted variables to be formatted or derived
-            if let Some(value) = variable_info.value() {
-                match variable_info.variable_type() {
-                    VariableType::Array => (),
-                    _ => {
-                        context.insert(identifier.as_str(), &archetect.render_string(value, context)?);
+                // Allow prompted variables to be formatted or derived
+                if let Some(value) = variable_info.value() {
+                    if let Some(value) = unless_missing(archetect, archetect.render_string(value, context))? {
+                        context.insert(identifier.as_str(), &value);
                     }
                 }
             }
         }
     }
 
-    Ok(())
+    Ok(prompted)
 }
 
-fn prompt_for_string(prompt: &mut String, default: &Option<String>, required: bool) -> Option<Value> {
-    if let Some(default) = &default {
-        prompt.push_str(format!("[{}] ", default).as_str());
-    };
-    let mut input_builder = input::<String>().msg(&prompt);
-
-    if required {
-        input_builder = input_builder
-            .add_test(|value| value.len() > 0)
-            .repeat_msg(&prompt)
-            .err("Please provide a value.");
+/// Passes a rendering through, except while a headless run is collecting the answers it is missing.
+/// Templates using a missing answer then fail, and give `None` instead, so the run carries on to report
+/// every missing answer at once.
+fn unless_missing<T>(archetect: &Archetect, result: Result<T, RenderError>) -> Result<Option<T>, ArchetectError> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(_) if archetect.headless() && archetect.has_missing_answers() => Ok(None),
+        Err(error) => Err(error.into()),
     }
+}
 
-    let value = if let Some(default) = &default {
-        input_builder.default(default.clone().to_owned()).get()
-    } else {
-        input_builder.get()
+/// Converts an answer to the variable's type, rendering any templates in it. Answers may be given as
+/// the type itself, such as a list or a number, or as text to be read as the type. Answers that cannot
+/// be converted are invalid, naming the variable by its `qualified` name.
+fn answer_value(
+    archetect: &Archetect,
+    context: &Context,
+    qualified: &str,
+    variable_info: &VariableInfo,
+    value: &Value,
+) -> Result<Value, ArchetectError> {
+    let variable_type = variable_info.variable_type();
+    let value = match (variable_type.clone(), value) {
+        (VariableType::Enum(options), value) => match scalar_text(value) {
+            Some(value) if options.contains(&value) => Some(Value::String(archetect.render_string(&value, context)?)),
+            _ => None,
+        },
+        (VariableType::MultiEnum(options), value) => list_items(archetect, context, value)?
+            .filter(|selections| selections.iter().all(|selection| options.contains(selection)))
+            .map(Value::from),
+        (VariableType::Array, value) => list_items(archetect, context, value)?.map(Value::from),
+        (VariableType::Object(schema), value) => match structure(value) {
+            Some(Value::Object(fields)) => Some(populate_object(archetect, &schema, &fields, context, qualified)?),
+            _ => None,
+        },
+        (VariableType::ObjectList(schema), value) => match structure(value) {
+            Some(Value::Array(items)) => {
+                let mut objects = vec![];
+                for (index, item) in items.iter().enumerate() {
+                    let fields = item.as_object().cloned().unwrap_or_default();
+                    let qualified = format!("{}[{}]", qualified, index);
+                    objects.push(populate_object(archetect, &schema, &fields, context, &qualified)?);
+                }
+                Some(Value::Array(objects))
+            }
+            _ => None,
+        },
+        (VariableType::Bool, Value::Bool(value)) => Some(Value::Bool(*value)),
+        (VariableType::Bool, Value::String(value)) => parse_bool(value).map(Value::Bool),
+        (VariableType::Int, Value::Number(value)) => value.as_i64().map(Value::from),
+        (VariableType::Int, Value::String(value)) => value.parse::<i64>().ok().map(Value::from),
+        (VariableType::Float, Value::Number(value)) => value.as_f64().map(Value::from),
+        (VariableType::Float, Value::String(value)) => value.parse::<f64>().ok().map(Value::from),
+        (VariableType::Bool, _) | (VariableType::Int, _) | (VariableType::Float, _) => None,
+        (VariableType::String, value) | (VariableType::Text, value) => match scalar_text(value) {
+            Some(value) => Some(Value::String(archetect.render_string(&value, context)?)),
+            None => None,
+        },
+        (VariableType::Path, value) => match scalar_text(value) {
+            Some(value) => Some(Value::String(expand_path(&archetect.render_string(&value, context)?))),
+            None => None,
+        },
+        (VariableType::Semver, value) => {
+            // Accept versions already exposed as objects, such as those included from another archetype.
+            let version = value.get("version").unwrap_or(value);
+            match scalar_text(version) {
+                Some(version) => {
+                    let version = archetect.render_string(&version, context)?;
+                    Version::parse(version.trim())
+                        .ok()
+                        .map(|_| Value::String(version.trim().to_owned()))
+                }
+                None => None,
+            }
+        }
+        // Secrets are taken as given, never rendered.
+        (VariableType::Secret, value) => scalar_text(value).map(Value::String),
     };
-    Some(Value::String(value))
+    value.ok_or_else(|| ArchetectError::InvalidAnswer {
+        identifier: qualified.to_owned(),
+        message: expected_answer(&variable_type),
+    })
 }
 
-fn prompt_for_int(prompt: &mut String, default: &Option<String>) -> Option<Value> {
-    let default = default.as_ref().map_or(None, |value| value.parse::<i64>().ok());
-
-    if let Some(default) = default {
-        prompt.push_str(format!("[{}] ", default).as_str());
+/// Describes the answers a variable of `variable_type` accepts.
+fn expected_answer(variable_type: &VariableType) -> String {
+    match variable_type {
+        VariableType::Enum(options) => format!("The value must be one of {}.", options.join(", ")),
+        VariableType::MultiEnum(options) => format!("The value must be a list of any of {}.", options.join(", ")),
+        VariableType::Array => "The value must be a list.".to_owned(),
+        VariableType::Object(_) => "The value must be an object.".to_owned(),
+        VariableType::ObjectList(_) => "The value must be a list of objects.".to_owned(),
+        VariableType::Bool => "The value must be true or false.".to_owned(),
+        VariableType::Int => "The value must be an integer.".to_owned(),
+        VariableType::Float => "The value must be a number.".to_owned(),
+        VariableType::Semver => "The value must be a semantic version.".to_owned(),
+        VariableType::String | VariableType::Text | VariableType::Path | VariableType::Secret => {
+            "The value must be text.".to_owned()
+        }
     }
+}
 
-    let input_builder = input::<i64>()
-        .msg(&prompt)
-        .err("Please specify an integer.")
-        .repeat_msg(&prompt);
-
-    let value = if let Some(default) = default {
-        input_builder.default(default).get()
-    } else {
-        input_builder.get()
-    };
+/// The text of a string, number or bool answer.
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.to_owned()),
+        Value::Number(_) | Value::Bool(_) => Some(value.to_string()),
+        _ => None,
+    }
+}
 
-    Some(Value::from(value))
+/// The text of an answer, with structures in their JSON form.
+fn answer_text(value: &Value) -> String {
+    scalar_text(value).unwrap_or_else(|| value.to_string())
 }
 
-fn prompt_for_bool(prompt: &mut String, default: &Option<String>) -> Option<Value> {
-    let default = default.as_ref().map_or(None, |value| {
-        let value = value.to_lowercase();
-        if ACCEPTABLE_BOOLEANS.contains(&value.as_str()) {
-            Some(value.to_owned())
-        } else {
-            None
+/// The items of a list answer, given either as a list or as text read by `parse_list`.
+fn list_items(archetect: &Archetect, context: &Context, value: &Value) -> Result<Option<Vec<String>>, ArchetectError> {
+    match value {
+        Value::Array(items) => {
+            let mut results = vec![];
+            for item in items {
+                match scalar_text(item) {
+                    Some(item) => results.push(archetect.render_string(&item, context)?),
+                    None => return Ok(None),
+                }
+            }
+            Ok(Some(results))
         }
-    });
-
-    if let Some(default) = default.clone() {
-        prompt.push_str(format!("[{}] ", default).as_str());
+        value => match scalar_text(value) {
+            Some(value) => Ok(Some(parse_list(&archetect.render_string(&value, context)?))),
+            None => Ok(None),
+        },
     }
+}
 
-    let input_builder = input::<String>()
-        .add_test(|value| ACCEPTABLE_BOOLEANS.contains(&value.to_lowercase().as_str()))
-        .msg(&prompt)
-        .err(format!("Please specify a value of {:?}.", ACCEPTABLE_BOOLEANS))
-        .repeat_msg(&prompt);
-
-    let value = if let Some(default) = default.clone() {
-        input_builder.default(default.to_owned()).get()
-    } else {
-        input_builder.get()
-    };
-
-    let value = match ACCEPTABLE_BOOLEANS.iter().position(|i| i == &value.as_str()).unwrap() {
-        0..=3 => true,
-        _ => false,
-    };
-
-    Some(Value::Bool(value))
+/// An object or list answer, given either as the structure itself or in its JSON form.
+fn structure(value: &Value) -> Option<Value> {
+    match value {
+        Value::String(value) => serde_json::from_str(value).ok(),
+        value => Some(value.clone()),
+    }
 }
 
-fn prompt_for_list(
+/// Prompts for a variable's value, asking again for as long as the answer fails validation. Objects
+/// are prompted for field by field, with `qualified` naming the variable they are set to.
+fn prompt_for_value(
     archetect: &Archetect,
     context: &Context,
-    prompt: &String,
+    qualified: &str,
+    prompt: &str,
+    default: Option<String>,
     variable_info: &VariableInfo,
-) -> Result<Option<Value>, ArchetectError> {
-    println!("{}", &prompt);
-
-    let mut results = vec![];
-
+) -> Result<Value, ArchetectError> {
+    let prompter = archetect.prompter();
     loop {
-        let count = results.len();
-        let mut input_builder = input::<String>().msg("Item: ");
-
-        if variable_info.required() {
-            input_builder = input_builder
-                .add_test(move |value| count > 0 || !value.trim().is_empty())
-                .err("This list requires at least one item.")
-                .repeat_msg(" - ")
+        let value = match prompt_once(archetect, context, qualified, prompt, default.as_deref(), variable_info) {
+            Err(ArchetectError::PromptError(PromptError::HelpRequested)) => {
+                prompter.help(prompt, &help_text(archetect, context, variable_info)?)?;
+                continue;
+            }
+            result => result?,
+        };
+
+        if let (VariableType::Semver, Value::String(version)) = (variable_info.variable_type(), &value) {
+            if Version::parse(version.trim()).is_err() {
+                prompter.reject(prompt, "Please specify a semantic version, such as 1.2.3.")?;
+                continue;
+            }
         }
-        let mut item = input_builder.get();
 
-        if item.trim().is_empty() {
-            break;
+        match variable_info.validate(&value) {
+            Ok(()) => return Ok(value),
+            Err(message) => prompter.reject(prompt, &message)?,
         }
+    }
+}
 
-        if let Some(value) = variable_info.value() {
-            let mut context = context.clone();
-            context.insert("item", &item);
-            item = archetect.render_string(value, &context)?;
+/// Asks for a variable's value once, as the prompt for its type.
+fn prompt_once(
+    archetect: &Archetect,
+    context: &Context,
+    qualified: &str,
+    prompt: &str,
+    default: Option<&str>,
+    variable_info: &VariableInfo,
+) -> Result<Value, ArchetectError> {
+    let prompter = archetect.prompter();
+    let value = match variable_info.variable_type() {
+        VariableType::Enum(values) => Value::String(prompter.prompt_enum(prompt, &values, default)?),
+        VariableType::MultiEnum(values) => {
+            let defaults = default.map(parse_list).unwrap_or_default();
+            Value::from(prompter.prompt_multi_enum(prompt, &values, &defaults, variable_info.required())?)
+        }
+        VariableType::Bool => {
+            let default = default.and_then(parse_bool);
+            Value::Bool(prompter.prompt_bool(prompt, default)?)
         }
+        VariableType::Int => {
+            let default = default.and_then(|value| value.parse::<i64>().ok());
+            Value::from(prompter.prompt_int(prompt, default)?)
+        }
+        VariableType::Float => {
+            let default = default.and_then(|value| value.parse::<f64>().ok());
+            Value::from(prompter.prompt_float(prompt, default)?)
+        }
+        VariableType::Array => Value::from(prompter.prompt_list(prompt, variable_info.required())?),
+        VariableType::Object(schema) => populate_object(archetect, &schema, &Map::new(), context, qualified)?,
+        VariableType::ObjectList(schema) => {
+            prompt_for_object_list(archetect, &schema, context, qualified, prompt, variable_info.required())?
+        }
+        VariableType::String | VariableType::Semver => {
+            Value::String(prompter.prompt_string(prompt, default, variable_info.required())?)
+        }
+        VariableType::Path => {
+            let value = prompter.prompt_string(prompt, default, variable_info.required())?;
+            Value::String(expand_path(&value))
+        }
+        VariableType::Text => Value::String(prompter.prompt_text(prompt, default, variable_info.required())?),
+        VariableType::Secret => Value::String(prompter.prompt_secret(prompt, variable_info.required())?),
+    };
+    Ok(value)
+}
 
-        results.push(Value::String(item));
+/// The help shown when `?` is answered at a variable's prompt: its description, help and example.
+fn help_text(archetect: &Archetect, context: &Context, variable_info: &VariableInfo) -> Result<String, ArchetectError> {
+    let mut sections = vec![];
+    if let Some(description) = variable_info.description() {
+        sections.push(archetect.render_string(description.trim(), context)?);
     }
-
-    Ok(Some(Value::Array(results)))
+    if let Some(help) = variable_info.help() {
+        sections.push(archetect.render_string(help.trim(), context)?);
+    }
+    if let Some(example) = variable_info.example() {
+        sections.push(format!(
+            "Example: {}",
+            archetect.render_string(example.trim(), context)?
+        ));
+    }
+    if sections.is_empty() {
+        sections.push("There is no help for this prompt.".to_owned());
+    }
+    Ok(sections.join("\n\n"))
 }
 
-fn prompt_for_enum(prompt: &mut String, options: &Vec<String>, default: &Option<String>) -> Option<Value> {
-    println!("{}", &prompt);
-    let choices = options
-        .iter()
-        .enumerate()
-        .map(|(id, entry)| (id + 1, entry.clone()))
-        .collect::<HashMap<_, _>>();
+/// Sets the fields of an object described by `schema`, taking answers from `fields` and prompting for
+/// the rest. Fields may refer to variables already set in `context`.
+fn populate_object(
+    archetect: &Archetect,
+    schema: &LinkedHashMap<String, VariableInfo>,
+    fields: &Map<String, Value>,
+    context: &Context,
+    qualified: &str,
+) -> Result<Value, ArchetectError> {
+    let mut answers = LinkedHashMap::new();
+    for (identifier, value) in fields {
+        answers.insert(identifier.to_owned(), AnswerInfo::with_value(value.clone()).build());
+    }
 
-    for (id, option) in options.iter().enumerate() {
-        println!("{:>2}) {}", id + 1, option);
+    let mut object_context = context.clone();
+    for identifier in schema.keys() {
+        object_context.remove(identifier);
     }
+    populate_variables(
+        archetect,
+        schema,
+        &answers,
+        &mut object_context,
+        &format!("{}.", qualified),
+    )?;
 
-    let mut message = String::from("Select and entry: ");
-    if let Some(default) = default {
-        if options.contains(default) {
-            message.push_str(format!("[{}] ", default).as_str());
+    let mut object = Map::new();
+    for identifier in schema.keys() {
+        if let Some(value) = object_context.get(identifier) {
+            object.insert(identifier.to_owned(), value.clone());
         }
-    };
+    }
+    Ok(Value::Object(object))
+}
 
-    let test_values = choices.keys().map(|v| *v).collect::<HashSet<_>>();
+/// Prompts for objects described by `schema` until no more are to be added. A required list is given
+/// its first object without asking.
+fn prompt_for_object_list(
+    archetect: &Archetect,
+    schema: &LinkedHashMap<String, VariableInfo>,
+    context: &Context,
+    qualified: &str,
+    prompt: &str,
+    required: bool,
+) -> Result<Value, ArchetectError> {
+    let mut objects = vec![];
+    loop {
+        if !(required && objects.is_empty()) {
+            let question = if objects.is_empty() {
+                format!("{} Add an item?", prompt.trim())
+            } else {
+                format!("{} Add another item?", prompt.trim())
+            };
+            if !archetect.prompter().confirm(&question, false)? {
+                break;
+            }
+        }
+        let qualified = format!("{}[{}]", qualified, objects.len());
+        objects.push(populate_object(archetect, schema, &Map::new(), context, &qualified)?);
+    }
+    Ok(Value::Array(objects))
+}
 
-    let input_builder = input::<usize>()
-        .msg(&message)
-        .add_test(move |value| test_values.contains(value))
-        .err("Please enter the number of a selection from the list.")
-        .repeat_msg(&message);
+/// Converts a rendered fallback to the variable's type where it can be, leaving it a string otherwise.
+fn fallback_value(variable_type: &VariableType, fallback: String) -> Value {
+    match variable_type {
+        VariableType::Bool => parse_bool(&fallback).map(Value::Bool),
+        VariableType::Int => fallback.trim().parse::<i64>().ok().map(Value::from),
+        VariableType::Float => fallback.trim().parse::<f64>().ok().map(Value::from),
+        VariableType::Array | VariableType::MultiEnum(_) => Some(Value::from(parse_list(&fallback))),
+        VariableType::Semver => Some(typed_value(variable_type, Value::String(fallback.clone()))),
+        _ => None,
+    }
+    .unwrap_or(Value::String(fallback))
+}
 
-    let value = if let Some(default) = default {
-        if let Some(index) = options.iter().position(|e| e.eq(default)) {
-            input_builder.default(index + 1).get()
-        } else {
-            input_builder.get()
-        }
-    } else {
-        input_builder.get()
-    };
+/// Expands `~` and environment variables in a path, leaving it as given if they cannot be expanded.
+fn expand_path(path: &str) -> String {
+    match shellexpand::full(path.trim()) {
+        Ok(expanded) => expanded.into_owned(),
+        Err(_) => path.trim().to_owned(),
+    }
+}
 
-    Some(Value::String(choices.get(&value).unwrap().to_owned()))
+/// Converts a validated answer to the value exposed to templates: semantic versions become objects,
+/// and everything else is exposed as it is.
+fn typed_value(variable_type: &VariableType, value: Value) -> Value {
+    match (variable_type, &value) {
+        (VariableType::Semver, Value::String(version)) => match Version::parse(version.trim()) {
+            Ok(version) => serde_json::json!({
+                "version": version.to_string(),
+                "major": version.major,
+                "minor": version.minor,
+                "patch": version.patch,
+                "pre": join_identifiers(&version.pre),
+                "build": join_identifiers(&version.build),
+            }),
+            Err(_) => value,
+        },
+        _ => value,
+    }
+}
+
+fn join_identifiers(identifiers: &[Identifier]) -> String {
+    identifiers
+        .iter()
+        .map(|identifier| identifier.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Formats each item of a prompted list with the variable's value, if it has one.
+fn render_list_items(
+    archetect: &Archetect,
+    context: &Context,
+    items: Vec<Value>,
+    variable_info: &VariableInfo,
+) -> Result<Vec<Value>, ArchetectError> {
+    let mut results = vec![];
+    for item in items {
+        match variable_info.value() {
+            Some(value) => {
+                let mut context = context.clone();
+                context.insert("item", &item);
+                results.push(Value::String(archetect.render_string(value, &context)?));
+            }
+            None => results.push(item),
+        }
+    }
+    Ok(results)
 }
 
 pub fn render_answers(
@@ -299,19 +584,41 @@ pub fn render_answers(
     for (identifier, answer_info) in answers {
         let mut result = AnswerInfo::new();
         if let Some(value) = answer_info.value() {
-            result = result.with_value(archetect.render_string(value, context)?);
+            result = result.with_value(render_value(archetect, value, context)?);
         }
         if let Some(prompt) = answer_info.prompt() {
             result = result.with_prompt(archetect.render_string(prompt, context)?);
         }
         if let Some(default) = answer_info.default() {
-            result = result.with_default(archetect.render_string(default, context)?);
+            result = result.with_default(render_value(archetect, default, context)?);
         }
         results.insert(identifier.to_owned(), result.build());
     }
     Ok(results)
 }
 
+/// Renders every string within `value`, leaving its structure as it is.
+fn render_value(archetect: &Archetect, value: &Value, context: &Context) -> Result<Value, ArchetectError> {
+    let value = match value {
+        Value::String(value) => Value::String(archetect.render_string(value, context)?),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| render_value(archetect, item, context))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(fields) => {
+            let mut object = Map::new();
+            for (key, value) in fields {
+                object.insert(key.to_owned(), render_value(archetect, value, context)?);
+            }
+            Value::Object(object)
+        }
+        value => value.clone(),
+    };
+    Ok(value)
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum VariableDescriptor {
     #[serde(rename = "object!")]
@@ -364,7 +671,12 @@ pub enum VariableDescriptor {
 
 #[cfg(test)]
 mod tests {
-    use crate::actions::set::VariableDescriptor;
+    use crate::actions::set::{populate_context, VariableDescriptor};
+    use crate::config::{AnswerConfig, AnswerInfo, Validation, VariableInfo, VariableType};
+    use crate::input::{FailingPrompter, PromptError, ScriptedPrompter};
+    use crate::template_engine::Context;
+    use crate::{Archetect, ArchetectError};
+    use indoc::indoc;
     use linked_hash_map::LinkedHashMap;
 
     #[test]
@@ -421,6 +733,680 @@ mod tests {
         println!("{}", yaml);
     }
 
+    #[test]
+    fn test_populate_context_from_prompts() {
+        let archetect = Archetect::builder()
+            .with_prompter(
+                ScriptedPrompter::new()
+                    .with_answer("Order Service")
+                    .with_answer(serde_json::Value::Null)
+                    .with_answer("no")
+                    .with_answer(json!(["orders", "customers"])),
+            )
+            .build()
+            .unwrap();
+
+        let mut variables = LinkedHashMap::new();
+        variables.insert("name".to_owned(), VariableInfo::with_prompt("Name:").build());
+        variables.insert(
+            "port".to_owned(),
+            VariableInfo::with_prompt("Port:")
+                .with_type(VariableType::Int)
+                .with_default("{{ 8000 + 80 }}")
+                .build(),
+        );
+        variables.insert(
+            "docker".to_owned(),
+            VariableInfo::with_prompt("Docker?")
+                .with_type(VariableType::Bool)
+                .build(),
+        );
+        variables.insert(
+            "tables".to_owned(),
+            VariableInfo::with_prompt("Tables:")
+                .with_type(VariableType::Array)
+                .with_value("{{ item | pascal_case }}")
+                .build(),
+        );
+        variables.insert(
+            "kind".to_owned(),
+            VariableInfo::with_prompt("Kind:")
+                .with_type(VariableType::Enum(vec!["lib".to_owned(), "bin".to_owned()]))
+                .build(),
+        );
+
+        let mut answers = LinkedHashMap::new();
+        answers.insert("kind".to_owned(), AnswerInfo::with_value("bin").build());
+
+        let mut context = Context::new();
+        populate_context(&archetect, &variables, &answers, &mut context).unwrap();
+
+        assert_eq!(context.get("name"), Some(&json!("Order Service")));
+        assert_eq!(context.get("port"), Some(&json!(8080)));
+        assert_eq!(context.get("docker"), Some(&json!(false)));
+        assert_eq!(context.get("tables"), Some(&json!(["Orders", "Customers"])));
+        assert_eq!(context.get("kind"), Some(&json!("bin")));
+    }
+
+    #[test]
+    fn test_populate_context_without_prompting() {
+        let archetect = Archetect::builder().with_prompter(FailingPrompter).build().unwrap();
+
+        let mut variables = LinkedHashMap::new();
+        variables.insert(
+            "port".to_owned(),
+            VariableInfo::with_prompt("Port:")
+                .with_type(VariableType::Int)
+                .with_default("8080")
+                .build(),
+        );
+        variables.insert("name".to_owned(), VariableInfo::with_prompt("Name:").build());
+
+        let mut context = Context::new();
+        match populate_context(&archetect, &variables, &LinkedHashMap::new(), &mut context) {
+            Err(ArchetectError::PromptError(PromptError::Unanswerable { prompt, .. })) => assert_eq!(prompt, "Name:"),
+            _ => panic!("Unanswerable expected"),
+        }
+        assert_eq!(context.get("port"), Some(&json!(8080)));
+    }
+
+    #[test]
+    fn test_populate_context_help() {
+        let archetect = Archetect::builder()
+            .with_prompter(
+                ScriptedPrompter::new()
+                    .with_answer("?")
+                    .with_answer("?")
+                    .with_answer(8080),
+            )
+            .build()
+            .unwrap();
+
+        let mut variables = LinkedHashMap::new();
+        variables.insert(
+            "port".to_owned(),
+            VariableInfo::with_prompt("Port:")
+                .with_type(VariableType::Int)
+                .with_description("The port {{ name }} listens on.")
+                .with_example("8080")
+                .build(),
+        );
+
+        let mut context = Context::new();
+        context.insert("name", "orders");
+        populate_context(&archetect, &variables, &LinkedHashMap::new(), &mut context).unwrap();
+        assert_eq!(context.get("port"), Some(&json!(8080)));
+
+        let archetect = Archetect::builder().with_headless(true).build().unwrap();
+        populate_context(&archetect, &variables, &LinkedHashMap::new(), &mut context).unwrap();
+        let missing_answers = archetect.take_missing_answers();
+        assert_eq!(missing_answers[0].description(), Some("The port orders listens on."));
+    }
+
+    #[test]
+    fn test_populate_context_headless_multi_enum() {
+        let archetect = Archetect::builder().with_headless(true).build().unwrap();
+
+        let mut variables = LinkedHashMap::new();
+        variables.insert(
+            "services".to_owned(),
+            VariableInfo::with_prompt("Services:")
+                .with_type(VariableType::MultiEnum(vec!["postgres".to_owned(), "kafka".to_owned()]))
+                .with_validation(Validation::new().with_min(1))
+                .build(),
+        );
+        variables.insert(
+            "extras".to_owned(),
+            serde_yaml::from_str(indoc!(
+                r#"
+                prompt: "Extras:"
+                type:
+                  multi-enum: [docs]
+                required: false
+                "#
+            ))
+            .unwrap(),
+        );
+
+        let mut context = Context::new();
+        populate_context(&archetect, &variables, &LinkedHashMap::new(), &mut context).unwrap();
+
+        let missing_answers = archetect.take_missing_answers();
+        assert_eq!(missing_answers.len(), 1);
+        assert_eq!(missing_answers[0].identifier(), "services");
+        assert_eq!(context.get("extras"), Some(&json!([])));
+    }
+
+    #[test]
+    fn test_populate_context_headless_dependents() {
+        let archetect = Archetect::builder().with_headless(true).build().unwrap();
+
+        let mut variables = LinkedHashMap::new();
+        variables.insert("name".to_owned(), VariableInfo::with_prompt("Name:").build());
+        variables.insert(
+            "slug".to_owned(),
+            VariableInfo::with_value("{{ name | train_case }}").build(),
+        );
+        variables.insert(
+            "database".to_owned(),
+            VariableInfo::with_prompt("Database for {{ name }}:")
+                .with_when("name | length > 3")
+                .build(),
+        );
+        variables.insert(
+            "title".to_owned(),
+            VariableInfo::with_prompt("Title for {{ slug }}:").build(),
+        );
+        variables.insert("kind".to_owned(), VariableInfo::with_prompt("Kind:").build());
+
+        let mut context = Context::new();
+        populate_context(&archetect, &variables, &LinkedHashMap::new(), &mut context).unwrap();
+
+        let missing_answers = archetect.take_missing_answers();
+        let identifiers = missing_answers
+            .iter()
+            .map(|missing_answer| missing_answer.identifier())
+            .collect::<Vec<_>>();
+        assert_eq!(identifiers, vec!["name", "title", "kind"]);
+        assert_eq!(missing_answers[1].prompt(), "Title for {{ slug }}:");
+        assert_eq!(context.get("slug"), None);
+    }
+
+    #[test]
+    fn test_populate_context_validation() {
+        let archetect = Archetect::builder()
+            .with_prompter(ScriptedPrompter::new().with_answer(99999).with_answer(8080))
+            .build()
+            .unwrap();
+
+        let mut variables = LinkedHashMap::new();
+        variables.insert(
+            "port".to_owned(),
+            VariableInfo::with_prompt("Port:")
+                .with_type(VariableType::Int)
+                .with_validation(Validation::new().with_min(1).with_max(65535))
+                .build(),
+        );
+        variables.insert(
+            "package".to_owned(),
+            VariableInfo::with_prompt("Package:")
+                .with_validation(Validation::new().with_allowed_chars("a-z0-9_"))
+                .build(),
+        );
+
+        let mut answers = LinkedHashMap::new();
+        answers.insert("package".to_owned(), AnswerInfo::with_value("order_service").build());
+
+        let mut context = Context::new();
+        populate_context(&archetect, &variables, &answers, &mut context).unwrap();
+        assert_eq!(context.get("port"), Some(&json!(8080)));
+        assert_eq!(context.get("package"), Some(&json!("order_service")));
+
+        let mut answers = LinkedHashMap::new();
+        answers.insert("port".to_owned(), AnswerInfo::with_value("8080").build());
+        answers.insert("package".to_owned(), AnswerInfo::with_value("order-service").build());
+        match populate_context(&archetect, &variables, &answers, &mut Context::new()) {
+            Err(ArchetectError::InvalidAnswer { identifier, .. }) => assert_eq!(identifier, "package"),
+            _ => panic!("InvalidAnswer expected"),
+        }
+    }
+
+    #[test]
+    fn test_populate_context_types() {
+        let archetect = Archetect::builder()
+            .with_prompter(
+                ScriptedPrompter::new()
+                    .with_answer(0.75)
+                    .with_answer("one.two")
+                    .with_answer("2.0.0-rc.1")
+                    .with_answer("First line\nSecond line")
+                    .with_answer("hunter2"),
+            )
+            .build()
+            .unwrap();
+
+        let variables = typed_variables();
+        let mut context = Context::new();
+        archetect.begin_report();
+        populate_context(&archetect, &variables, &LinkedHashMap::new(), &mut context).unwrap();
+        let report = archetect.end_report();
+
+        assert_eq!(context.get("ratio"), Some(&json!(0.75)));
+        assert_eq!(context.get("package"), Some(&json!("one.two")));
+        assert_eq!(
+            context.get("version"),
+            Some(&json!({
+                "version": "2.0.0-rc.1",
+                "major": 2,
+                "minor": 0,
+                "patch": 0,
+                "pre": "rc.1",
+                "build": "",
+            }))
+        );
+        assert_eq!(context.get("notes"), Some(&json!("First line\nSecond line")));
+        assert_eq!(context.get("password"), Some(&json!("hunter2")));
+        assert!(report.answers().get("version").is_some());
+        assert!(report.answers().get("password").is_none());
+    }
+
+    #[test]
+    fn test_populate_context_nested_secrets() {
+        let answers: AnswerConfig = serde_yaml::from_str(indoc!(
+            r#"
+            answers:
+              db:
+                value:
+                  host: localhost
+                  password: hunter2
+              users:
+                value:
+                  - name: admin
+                    token: s3cret
+            "#
+        ))
+        .unwrap();
+
+        let mut db = LinkedHashMap::new();
+        db.insert("host".to_owned(), VariableInfo::with_prompt("Host:").build());
+        db.insert(
+            "password".to_owned(),
+            VariableInfo::with_prompt("Password:")
+                .with_type(VariableType::Secret)
+                .build(),
+        );
+        let mut user = LinkedHashMap::new();
+        user.insert("name".to_owned(), VariableInfo::with_prompt("Name:").build());
+        user.insert(
+            "token".to_owned(),
+            VariableInfo::with_prompt("Token:")
+                .with_type(VariableType::Secret)
+                .build(),
+        );
+        let mut variables = LinkedHashMap::new();
+        variables.insert(
+            "db".to_owned(),
+            VariableInfo::with_prompt("Database:")
+                .with_type(VariableType::Object(db))
+                .build(),
+        );
+        variables.insert(
+            "users".to_owned(),
+            VariableInfo::with_prompt("Users:")
+                .with_type(VariableType::ObjectList(user))
+                .build(),
+        );
+
+        let archetect = Archetect::builder().with_headless(true).build().unwrap();
+        let mut context = Context::new();
+        archetect.begin_report();
+        populate_context(&archetect, &variables, answers.answers(), &mut context).unwrap();
+        let report = archetect.end_report();
+
+        assert_eq!(context.get("db").unwrap()["password"], json!("hunter2"));
+        assert_eq!(context.get("users").unwrap()[0]["token"], json!("s3cret"));
+        assert_eq!(report.answers().get("db"), Some(&json!({ "host": "localhost" })));
+        assert_eq!(report.answers().get("users"), Some(&json!([{ "name": "admin" }])));
+    }
+
+    #[test]
+    fn test_populate_context_typed_answers() {
+        let archetect = Archetect::builder().with_prompter(FailingPrompter).build().unwrap();
+
+        let mut variables = typed_variables();
+        variables.insert(
+            "workspace".to_owned(),
+            VariableInfo::with_prompt("Workspace:")
+                .with_type(VariableType::Path)
+                .build(),
+        );
+
+        let mut answers = LinkedHashMap::new();
+        answers.insert("ratio".to_owned(), AnswerInfo::with_value("1.5").build());
+        answers.insert("notes".to_owned(), AnswerInfo::with_value("{{ ratio }}").build());
+        answers.insert("version".to_owned(), AnswerInfo::with_value("1.2.3").build());
+        answers.insert("password".to_owned(), AnswerInfo::with_value("{{ secret }}").build());
+        answers.insert("workspace".to_owned(), AnswerInfo::with_value("$HOME/orders").build());
+
+        let mut context = Context::new();
+        populate_context(&archetect, &variables, &answers, &mut context).unwrap();
+        assert_eq!(context.get("ratio"), Some(&json!(1.5)));
+        assert_eq!(context.get("notes"), Some(&json!("1.5")));
+        assert_eq!(
+            context.get("version").and_then(|version| version.get("minor")),
+            Some(&json!(2))
+        );
+        assert_eq!(context.get("password"), Some(&json!("{{ secret }}")));
+        // Read the environment rather than setting it, as tests run in parallel.
+        let home = std::env::var("HOME").unwrap();
+        assert_eq!(context.get("workspace"), Some(&json!(format!("{}/orders", home))));
+
+        answers.insert("version".to_owned(), AnswerInfo::with_value("1.2").build());
+        match populate_context(&archetect, &variables, &answers, &mut Context::new()) {
+            Err(ArchetectError::InvalidAnswer { identifier, .. }) => assert_eq!(identifier, "version"),
+            _ => panic!("InvalidAnswer expected"),
+        }
+
+        let mut variables = LinkedHashMap::new();
+        variables.insert(
+            "port".to_owned(),
+            VariableInfo::with_prompt("Port:").with_type(VariableType::Int).build(),
+        );
+        let mut answers = LinkedHashMap::new();
+        answers.insert("port".to_owned(), AnswerInfo::with_value("eighty").build());
+        match populate_context(&archetect, &variables, &answers, &mut Context::new()) {
+            Err(ArchetectError::InvalidAnswer { identifier, message }) => {
+                assert_eq!(identifier, "port");
+                assert_eq!(message, "The value must be an integer.");
+            }
+            _ => panic!("InvalidAnswer expected"),
+        }
+    }
+
+    #[test]
+    fn test_populate_context_multi_enum() {
+        let archetect = Archetect::builder()
+            .with_prompter(
+                ScriptedPrompter::new()
+                    .with_answer(json!([]))
+                    .with_answer("postgres, kafka")
+                    .with_answer(serde_json::Value::Null),
+            )
+            .build()
+            .unwrap();
+
+        let options = vec!["postgres", "redis", "kafka", "s3"]
+            .into_iter()
+            .map(|option| option.to_owned())
+            .collect::<Vec<_>>();
+        let mut variables = LinkedHashMap::new();
+        variables.insert(
+            "stores".to_owned(),
+            VariableInfo::with_prompt("Stores:")
+                .with_type(VariableType::MultiEnum(options.clone()))
+                .with_validation(Validation::new().with_min(1))
+                .build(),
+        );
+        variables.insert(
+            "caches".to_owned(),
+            VariableInfo::with_prompt("Caches:")
+                .with_type(VariableType::MultiEnum(options.clone()))
+                .with_default("[redis]")
+                .build(),
+        );
+        variables.insert(
+            "queues".to_owned(),
+            VariableInfo::with_prompt("Queues:")
+                .with_type(VariableType::MultiEnum(options))
+                .build(),
+        );
+
+        let mut answers = LinkedHashMap::new();
+        answers.insert("queues".to_owned(), AnswerInfo::with_value("kafka,s3").build());
+
+        let mut context = Context::new();
+        populate_context(&archetect, &variables, &answers, &mut context).unwrap();
+        assert_eq!(context.get("stores"), Some(&json!(["postgres", "kafka"])));
+        assert_eq!(context.get("caches"), Some(&json!(["redis"])));
+        assert_eq!(context.get("queues"), Some(&json!(["kafka", "s3"])));
+        assert_eq!(
+            archetect
+                .render_string("{% if stores is containing(\"kafka\") %}kafka{% endif %}", &context)
+                .unwrap(),
+            "kafka"
+        );
+    }
+
+    #[test]
+    fn test_populate_context_when() {
+        let archetect = Archetect::builder()
+            .with_prompter(
+                ScriptedPrompter::new()
+                    .with_answer("no")
+                    .with_answer("yes")
+                    .with_answer("orders"),
+            )
+            .build()
+            .unwrap();
+
+        let mut variables = LinkedHashMap::new();
+        variables.insert(
+            "docker".to_owned(),
+            VariableInfo::with_prompt("Docker?")
+                .with_type(VariableType::Bool)
+                .build(),
+        );
+        variables.insert(
+            "docker_port".to_owned(),
+            VariableInfo::with_prompt("Docker Port:")
+                .with_type(VariableType::Int)
+                .with_when("docker")
+                .with_fallback("0")
+                .build(),
+        );
+        variables.insert(
+            "database".to_owned(),
+            VariableInfo::with_prompt("Database?")
+                .with_type(VariableType::Bool)
+                .build(),
+        );
+        variables.insert(
+            "database_name".to_owned(),
+            VariableInfo::with_prompt("Database Name:")
+                .with_when("database and not docker")
+                .build(),
+        );
+        variables.insert(
+            "schema".to_owned(),
+            VariableInfo::with_prompt("Schema:")
+                .with_when("database_name == 'customers'")
+                .build(),
+        );
+
+        let mut context = Context::new();
+        populate_context(&archetect, &variables, &LinkedHashMap::new(), &mut context).unwrap();
+        assert_eq!(context.get("docker_port"), Some(&json!(0)));
+        assert_eq!(context.get("database_name"), Some(&json!("orders")));
+        assert_eq!(context.get("schema"), None);
+    }
+
+    #[test]
+    fn test_populate_context_objects() {
+        let archetect = Archetect::builder()
+            .with_prompter(
+                ScriptedPrompter::new()
+                    .with_answer("Order")
+                    .with_answer("id")
+                    .with_answer("Integer")
+                    .with_answer("yes")
+                    .with_answer("total")
+                    .with_answer(serde_json::Value::Null)
+                    .with_answer("no")
+                    .with_answer("no"),
+            )
+            .build()
+            .unwrap();
+
+        let mut context = Context::new();
+        populate_context(&archetect, &entity_variables(), &LinkedHashMap::new(), &mut context).unwrap();
+        assert_eq!(
+            context.get("entities"),
+            Some(&json!([{
+                "name": "Order",
+                "fields": [
+                    { "name": "id", "type": "Integer" },
+                    { "name": "total", "type": "String" },
+                ],
+            }]))
+        );
+    }
+
+    #[test]
+    fn test_populate_context_object_answers() {
+        let answers: AnswerConfig = serde_yaml::from_str(indoc!(
+            r#"
+            answers:
+              entities:
+                value:
+                  - name: "{{ 'order' | pascal_case }}"
+                    fields:
+                      - name: id
+                        type: Integer
+                  - name: Customer
+                    fields:
+                      - type: Integer
+            "#
+        ))
+        .unwrap();
+
+        let archetect = Archetect::builder().with_headless(true).build().unwrap();
+        let mut context = Context::new();
+        populate_context(&archetect, &entity_variables(), answers.answers(), &mut context).unwrap();
+        assert_eq!(context.get("entities").unwrap()[0]["name"], json!("Order"));
+        assert_eq!(
+            context.get("entities").unwrap()[0]["fields"][0]["type"],
+            json!("Integer")
+        );
+
+        let missing = archetect.take_missing_answers();
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].identifier(), "entities[1].fields[0].name");
+    }
+
+    #[test]
+    fn test_populate_context_structured_answers() {
+        let answers: AnswerConfig = serde_yaml::from_str(indoc!(
+            r#"
+            answers:
+              port:
+                value: 8080
+              tls:
+                value: true
+              modules:
+                value: [orders, "{{ 'customer' }}s"]
+              stores:
+                value: [postgres]
+              db.host:
+                value: localhost
+            "#
+        ))
+        .unwrap();
+
+        let mut db = LinkedHashMap::new();
+        db.insert("host".to_owned(), VariableInfo::with_prompt("Host:").build());
+        db.insert(
+            "port".to_owned(),
+            VariableInfo::with_prompt("Port:")
+                .with_type(VariableType::Int)
+                .with_default("5432")
+                .build(),
+        );
+
+        let mut variables = LinkedHashMap::new();
+        variables.insert(
+            "port".to_owned(),
+            VariableInfo::with_prompt("Port:").with_type(VariableType::Int).build(),
+        );
+        variables.insert(
+            "tls".to_owned(),
+            VariableInfo::with_prompt("TLS?").with_type(VariableType::Bool).build(),
+        );
+        variables.insert(
+            "modules".to_owned(),
+            VariableInfo::with_prompt("Modules:")
+                .with_type(VariableType::Array)
+                .with_value("{{ item | pascal_case }}")
+                .build(),
+        );
+        variables.insert(
+            "stores".to_owned(),
+            VariableInfo::with_prompt("Stores:")
+                .with_type(VariableType::MultiEnum(vec!["postgres".to_owned(), "kafka".to_owned()]))
+                .build(),
+        );
+        variables.insert(
+            "db".to_owned(),
+            VariableInfo::with_prompt("Database:")
+                .with_type(VariableType::Object(db))
+                .build(),
+        );
+
+        let archetect = Archetect::builder().with_prompter(FailingPrompter).build().unwrap();
+        let mut context = Context::new();
+        populate_context(&archetect, &variables, answers.answers(), &mut context).unwrap();
+        assert_eq!(context.get("port"), Some(&json!(8080)));
+        assert_eq!(context.get("tls"), Some(&json!(true)));
+        assert_eq!(context.get("modules"), Some(&json!(["Orders", "Customers"])));
+        assert_eq!(context.get("stores"), Some(&json!(["postgres"])));
+        assert_eq!(context.get("db"), Some(&json!({"host": "localhost", "port": 5432})));
+    }
+
+    fn entity_variables() -> LinkedHashMap<String, VariableInfo> {
+        let mut fields = LinkedHashMap::new();
+        fields.insert("name".to_owned(), VariableInfo::with_prompt("Field Name:").build());
+        fields.insert(
+            "type".to_owned(),
+            VariableInfo::with_prompt("Field Type:")
+                .with_type(VariableType::Enum(vec!["String".to_owned(), "Integer".to_owned()]))
+                .with_default("String")
+                .build(),
+        );
+
+        let mut entity = LinkedHashMap::new();
+        entity.insert("name".to_owned(), VariableInfo::with_prompt("Entity Name:").build());
+        entity.insert(
+            "fields".to_owned(),
+            VariableInfo::with_prompt("Fields:")
+                .with_type(VariableType::ObjectList(fields))
+                .build(),
+        );
+
+        let mut variables = LinkedHashMap::new();
+        variables.insert(
+            "entities".to_owned(),
+            VariableInfo::with_prompt("Entities:")
+                .with_type(VariableType::ObjectList(entity))
+                .build(),
+        );
+        variables
+    }
+
+    fn typed_variables() -> LinkedHashMap<String, VariableInfo> {
+        let mut variables = LinkedHashMap::new();
+        variables.insert(
+            "ratio".to_owned(),
+            VariableInfo::with_prompt("Ratio:")
+                .with_type(VariableType::Float)
+                .build(),
+        );
+        variables.insert(
+            "package".to_owned(),
+            VariableInfo::with_prompt("Package:")
+                .with_type(VariableType::Path)
+                .with_default("one/two")
+                .build(),
+        );
+        variables.insert(
+            "version".to_owned(),
+            VariableInfo::with_prompt("Version:")
+                .with_type(VariableType::Semver)
+                .build(),
+        );
+        variables.insert(
+            "notes".to_owned(),
+            VariableInfo::with_prompt("Notes:")
+                .with_type(VariableType::Text)
+                .build(),
+        );
+        variables.insert(
+            "password".to_owned(),
+            VariableInfo::with_prompt("Password:")
+                .with_type(VariableType::Secret)
+                .build(),
+        );
+        variables
+    }
+
     fn values_map<K: Into<String>, V>(values: Vec<(K, V)>) -> LinkedHashMap<String, Box<V>> {
         let mut results = LinkedHashMap::new();
         for (identifier, value) in values {
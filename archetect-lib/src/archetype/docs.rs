@@ -0,0 +1,189 @@
+use linked_hash_map::LinkedHashMap;
+
+use crate::actions::ActionId;
+use crate::config::{VariableInfo, VariableType};
+use crate::Archetype;
+
+/// Documents an archetype in Markdown: its description, then each variable its script may ask for,
+/// wherever in the script it is set. Wholly derived variables are never asked for, and are left out.
+pub fn describe(archetype: &Archetype) -> String {
+    let config = archetype.configuration();
+    let mut lines = vec![format!("# {}", archetype.source().source())];
+    if let Some(description) = config.description() {
+        lines.push(String::new());
+        lines.push(description.trim().to_owned());
+    }
+
+    let mut variables = LinkedHashMap::new();
+    collect_variables(config.actions(), &mut variables);
+    if !variables.is_empty() {
+        lines.push(String::new());
+        lines.push("## Variables".to_owned());
+        for (identifier, variable_info) in &variables {
+            describe_variable(&mut lines, identifier, variable_info);
+        }
+    }
+
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Gathers the variables of every `set` action within `actions`, in the order they appear.
+fn collect_variables(actions: &[ActionId], variables: &mut LinkedHashMap<String, VariableInfo>) {
+    for action in actions {
+        match action {
+            ActionId::Set(set_variables) => {
+                for (identifier, variable_info) in set_variables {
+                    variables.insert(identifier.to_owned(), variable_info.clone());
+                }
+            }
+            ActionId::Scope(action) => collect_variables(action.actions(), variables),
+            ActionId::Actions(actions) | ActionId::Loop(actions) => collect_variables(actions, variables),
+            ActionId::If(action) => {
+                collect_variables(action.then_actions(), variables);
+                if let Some(actions) = action.else_actions() {
+                    collect_variables(actions, variables);
+                }
+            }
+            ActionId::ForEach(action) => collect_variables(action.actions(), variables),
+            ActionId::For(action) => collect_variables(action.actions(), variables),
+            _ => (),
+        }
+    }
+}
+
+fn describe_variable(lines: &mut Vec<String>, identifier: &str, variable_info: &VariableInfo) {
+    if variable_info.has_derived_value() {
+        return;
+    }
+
+    lines.push(String::new());
+    lines.push(format!("### `{}`", identifier));
+    for text in variable_info.description().iter().chain(variable_info.help().iter()) {
+        lines.push(String::new());
+        lines.push(text.trim().to_owned());
+    }
+
+    lines.push(String::new());
+    if let Some(prompt) = variable_info.prompt() {
+        lines.push(format!("- Prompt: {}", prompt.trim()));
+    }
+    lines.push(format!("- Type: {}", type_name(&variable_info.variable_type())));
+    if let Some(default) = variable_info.default() {
+        lines.push(format!("- Default: `{}`", default));
+    }
+    if let Some(example) = variable_info.example() {
+        lines.push(format!("- Example: `{}`", example.trim()));
+    }
+    if let Some(condition) = variable_info.when() {
+        lines.push(format!("- Asked when: `{}`", condition.trim()));
+    }
+    if !variable_info.required() {
+        lines.push("- Optional".to_owned());
+    }
+
+    match variable_info.variable_type() {
+        VariableType::Object(schema) => {
+            for (field, field_info) in &schema {
+                describe_variable(lines, &format!("{}.{}", identifier, field), field_info);
+            }
+        }
+        VariableType::ObjectList(schema) => {
+            for (field, field_info) in &schema {
+                describe_variable(lines, &format!("{}[].{}", identifier, field), field_info);
+            }
+        }
+        _ => (),
+    }
+}
+
+fn type_name(variable_type: &VariableType) -> String {
+    match variable_type {
+        VariableType::String => "string".to_owned(),
+        VariableType::Int => "integer".to_owned(),
+        VariableType::Float => "number".to_owned(),
+        VariableType::Bool => "boolean".to_owned(),
+        VariableType::Enum(options) => format!("one of {}", options.join(", ")),
+        VariableType::MultiEnum(options) => format!("any of {}", options.join(", ")),
+        VariableType::Array => "list".to_owned(),
+        VariableType::Object(_) => "object".to_owned(),
+        VariableType::ObjectList(_) => "list of objects".to_owned(),
+        VariableType::Path => "path".to_owned(),
+        VariableType::Semver => "semantic version".to_owned(),
+        VariableType::Text => "text".to_owned(),
+        VariableType::Secret => "secret".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use indoc::indoc;
+
+    use crate::util::Source;
+    use crate::Archetype;
+
+    #[test]
+    fn test_describe() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path();
+        fs::write(
+            root.join("archetype.yml"),
+            indoc!(
+                r#"
+                description: A service with a database.
+                script:
+                  - set:
+                      name:
+                        prompt: "Service Name:"
+                        description: The name of the service.
+                        help: |
+                          Used for the project directory and the main package.
+                        example: Order Service
+                      slug:
+                        value: "{{ name | train_case }}"
+                  - if:
+                      conditions:
+                        - switch-enabled: db
+                      then:
+                        - set:
+                            port:
+                              prompt: "Port:"
+                              type: int
+                              default: "8080"
+                              required: false
+                "#
+            ),
+        )
+        .unwrap();
+        let archetype = Archetype::from_source(&Source::LocalDirectory { path: root.to_owned() }).unwrap();
+
+        let expected = indoc!(
+            r#"
+
+            A service with a database.
+
+            ## Variables
+
+            ### `name`
+
+            The name of the service.
+
+            Used for the project directory and the main package.
+
+            - Prompt: Service Name:
+            - Type: string
+            - Example: `Order Service`
+
+            ### `port`
+
+            - Prompt: Port:
+            - Type: integer
+            - Default: `8080`
+            - Optional
+            "#
+        );
+        assert_eq!(archetype.describe(), format!("# {}\n{}", root.display(), expected));
+    }
+}
@@ -3,18 +3,22 @@ use std::path::{Path, PathBuf};
 
 use linked_hash_map::LinkedHashMap;
 
-use crate::actions::ActionId;
+use crate::actions::{review, ActionId};
 use crate::config::{AnswerInfo, ArchetypeConfig};
 use crate::errors::RenderError;
+use crate::report::RenderReport;
 use crate::rules::RulesContext;
-use crate::template_engine::Context;
+use crate::template_engine::{Context, Tera};
 use crate::util::{Source, SourceError};
 use crate::{Archetect, ArchetectError};
 
+mod docs;
+
 pub struct Archetype {
     source: Source,
     config: ArchetypeConfig,
     path: PathBuf,
+    template_engine: Tera,
 }
 
 impl Archetype {
@@ -23,10 +27,24 @@ impl Archetype {
 
         let config = ArchetypeConfig::load(local_path)?;
 
+        let template_engine = match config.templates() {
+            Some(templates) => {
+                let directory = local_path.join(templates);
+                if !directory.is_dir() {
+                    return Err(ArchetypeError::TemplatesNotFound(directory));
+                }
+                let glob = directory.join("**").join("*");
+                Tera::new(&glob.to_string_lossy())
+                    .map_err(|error| ArchetypeError::TemplateError { path: directory, error })?
+            }
+            None => Tera::default(),
+        };
+
         let archetype = Archetype {
             config,
             source: source.clone(),
             path: local_path.to_owned(),
+            template_engine,
         };
 
         Ok(archetype)
@@ -44,16 +62,27 @@ impl Archetype {
         &self.source
     }
 
+    /// Documents this archetype in Markdown, with every variable its script may ask for.
+    pub fn describe(&self) -> String {
+        docs::describe(self)
+    }
+
+    /// The template engine for this archetype's content, with its template library loaded.
+    pub fn template_engine(&self) -> &Tera {
+        &self.template_engine
+    }
+
     pub fn execute_script<D: AsRef<Path>>(
         &self,
         archetect: &Archetect,
         destination: D,
         answers: &LinkedHashMap<String, AnswerInfo>,
-    ) -> Result<(), ArchetectError> {
+    ) -> Result<RenderReport, ArchetectError> {
         let destination = destination.as_ref();
         fs::create_dir_all(destination)?;
 
         let mut rules_context = RulesContext::new();
+        rules_context.set_template_suffixes(self.config.template_suffixes());
         let mut context = Context::new();
 
         use clap::crate_version;
@@ -70,9 +99,36 @@ impl Archetype {
         };
         context.insert("archetype", &archetype_info);
 
-        let root_action = ActionId::from(self.config.actions());
+        // Review the answers when the archetype asks for it, or when it is the one being rendered and the
+        // review was asked for. There is nobody to review them when headless.
+        let review = !archetect.headless() && (self.config.confirm() || archetect.confirm() && !archetect.in_run());
+
+        archetect.begin_report();
+        let result = if review {
+            review::execute_with_review(
+                archetect,
+                self,
+                destination,
+                &mut rules_context,
+                answers,
+                &mut context,
+                self.config.actions(),
+            )
+        } else {
+            let root_action = ActionId::from(self.config.actions());
+            root_action.execute(archetect, self, destination, &mut rules_context, answers, &mut context)
+        };
+        let report = archetect.end_report();
 
-        root_action.execute(archetect, self, destination, &mut rules_context, answers, &mut context)
+        // Once the outermost run finishes, report every answer a headless run was missing. Errors from
+        // actions that needed those answers are superseded by the missing answers themselves.
+        if !archetect.in_run() {
+            let missing_answers = archetect.take_missing_answers();
+            if !missing_answers.is_empty() {
+                return Err(ArchetectError::MissingAnswers(missing_answers));
+            }
+        }
+        result.map(|_| report)
     }
 }
 
@@ -97,7 +153,15 @@ pub enum ArchetypeError {
     SourceError(SourceError),
     RenderError(RenderError),
     IoError(std::io::Error),
-    YamlError { path: PathBuf, cause: serde_yaml::Error },
+    YamlError {
+        path: PathBuf,
+        cause: serde_yaml::Error,
+    },
+    TemplatesNotFound(PathBuf),
+    TemplateError {
+        path: PathBuf,
+        error: crate::template_engine::Error,
+    },
 }
 
 impl From<SourceError> for ArchetypeError {
@@ -120,9 +184,354 @@ impl From<std::io::Error> for ArchetypeError {
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
     use std::path::Path;
 
     use glob::Pattern;
+    use indoc::indoc;
+    use linked_hash_map::LinkedHashMap;
+
+    use crate::config::AnswerInfo;
+    use crate::input::{MissingAnswer, PromptType, ScriptedPrompter};
+    use crate::report::PathOutcome;
+    use crate::rules::RulesContext;
+    use crate::template_engine::Context;
+    use crate::util::Source;
+    use crate::{Archetect, ArchetectError, Archetype};
+
+    #[test]
+    fn test_render_with_template_library() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path();
+        fs::create_dir_all(root.join("templates/partials")).unwrap();
+        fs::create_dir_all(root.join("contents")).unwrap();
+        fs::write(root.join("archetype.yml"), "templates: templates\n").unwrap();
+        fs::write(root.join("templates/partials/license.txt"), "Copyright {{ author }}").unwrap();
+        fs::write(
+            root.join("templates/macros.tera"),
+            "{% macro greet(name) %}Hello, {{ name }}!{% endmacro greet %}",
+        )
+        .unwrap();
+        fs::write(
+            root.join("templates/base.tera"),
+            "{% block header %}# Header{% endblock header %}\n{% block body %}{% endblock body %}",
+        )
+        .unwrap();
+        fs::write(
+            root.join("contents/README.md"),
+            indoc!(
+                r#"
+                {%- extends "base.tera" -%}
+                {%- import "macros.tera" as m -%}
+                {% block body %}{{ m::greet(name=author) }}
+                {% include "partials/license.txt" %}{% endblock body %}"#
+            ),
+        )
+        .unwrap();
+
+        let archetect = Archetect::build().unwrap();
+        let archetype = Archetype::from_source(&Source::LocalDirectory { path: root.to_owned() }).unwrap();
+        let mut context = Context::new();
+        context.insert("author", "Jane");
+        let destination = tempfile::tempdir().unwrap();
+        archetect
+            .render_directory_with(
+                archetype.template_engine(),
+                &context,
+                root.join("contents"),
+                destination.path(),
+                &mut RulesContext::new(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(destination.path().join("README.md")).unwrap(),
+            "# Header\nHello, Jane!\nCopyright Jane"
+        );
+    }
+
+    #[test]
+    fn test_execute_script_report() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path();
+        fs::create_dir_all(root.join("contents")).unwrap();
+        fs::write(root.join("contents/{{ name }}.md"), "# {{ name }}").unwrap();
+        fs::write(
+            root.join("archetype.yml"),
+            indoc!(
+                r#"
+                script:
+                  - set:
+                      name:
+                        value: example
+                  - render:
+                      directory:
+                        source: contents
+                "#
+            ),
+        )
+        .unwrap();
+
+        let archetect = Archetect::build().unwrap();
+        let archetype = Archetype::from_source(&Source::LocalDirectory { path: root.to_owned() }).unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let report = archetype
+            .execute_script(&archetect, destination.path(), &LinkedHashMap::new())
+            .unwrap();
+
+        assert_eq!(report.paths().len(), 1);
+        assert_eq!(report.paths()[0].path(), destination.path().join("example.md"));
+        assert_eq!(report.paths()[0].outcome(), PathOutcome::Rendered);
+        assert_eq!(report.answers().get("name"), Some(&serde_json::Value::from("example")));
+
+        let report = archetype
+            .execute_script(&archetect, destination.path(), &LinkedHashMap::new())
+            .unwrap();
+        assert_eq!(report.count(PathOutcome::Preserved), 1);
+    }
+
+    #[test]
+    fn test_execute_script_headless() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path();
+        fs::create_dir_all(root.join("contents")).unwrap();
+        fs::write(root.join("contents/{{ name }}.md"), "# {{ name }}").unwrap();
+        fs::write(
+            root.join("archetype.yml"),
+            indoc!(
+                r#"
+                script:
+                  - set:
+                      name:
+                        prompt: "Name:"
+                      port:
+                        prompt: "Port:"
+                        type: int
+                        default: "8080"
+                  - render:
+                      directory:
+                        source: contents
+                  - set:
+                      kind:
+                        prompt: "Kind:"
+                        type:
+                          enum: [lib, bin]
+                "#
+            ),
+        )
+        .unwrap();
+
+        let archetect = Archetect::builder().with_headless(true).build().unwrap();
+        let archetype = Archetype::from_source(&Source::LocalDirectory { path: root.to_owned() }).unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        match archetype.execute_script(&archetect, destination.path(), &LinkedHashMap::new()) {
+            Err(ArchetectError::MissingAnswers(missing)) => assert_eq!(
+                missing,
+                vec![
+                    MissingAnswer::new("name", "Name:", PromptType::String),
+                    MissingAnswer::new("kind", "Kind:", PromptType::Enum),
+                ]
+            ),
+            _ => panic!("MissingAnswers expected"),
+        }
+        assert!(fs::read_dir(destination.path()).unwrap().next().is_none());
+
+        let mut answers = LinkedHashMap::new();
+        answers.insert("name".to_owned(), AnswerInfo::with_value("example").build());
+        answers.insert("kind".to_owned(), AnswerInfo::with_value("lib").build());
+        let report = archetype
+            .execute_script(&archetect, destination.path(), &answers)
+            .unwrap();
+        assert_eq!(report.answers().get("port"), Some(&serde_json::Value::from(8080)));
+        assert_eq!(report.count(PathOutcome::Rendered), 1);
+    }
+
+    #[test]
+    fn test_execute_script_confirm() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path();
+        fs::create_dir_all(root.join("contents")).unwrap();
+        fs::write(root.join("contents/{{ slug }}.md"), "# {{ name }} by {{ author }}").unwrap();
+        fs::write(
+            root.join("archetype.yml"),
+            indoc!(
+                r#"
+                confirm: true
+                script:
+                  - set:
+                      name:
+                        prompt: "Name:"
+                      author:
+                        prompt: "Author:"
+                      slug:
+                        value: "{{ name | train_case }}"
+                  - render:
+                      directory:
+                        source: contents
+                "#
+            ),
+        )
+        .unwrap();
+        let archetype = Archetype::from_source(&Source::LocalDirectory { path: root.to_owned() }).unwrap();
+        let destination = tempfile::tempdir().unwrap();
+
+        let archetect = Archetect::builder()
+            .with_prompter(
+                ScriptedPrompter::new()
+                    .with_answer("Order Service")
+                    .with_answer("Jane")
+                    .with_answer("Abort"),
+            )
+            .build()
+            .unwrap();
+        match archetype.execute_script(&archetect, destination.path(), &LinkedHashMap::new()) {
+            Err(ArchetectError::Aborted) => (),
+            _ => panic!("Aborted expected"),
+        }
+        assert!(fs::read_dir(destination.path()).unwrap().next().is_none());
+
+        // Change the name, which the slug is derived from, before accepting.
+        let archetect = Archetect::builder()
+            .with_prompter(
+                ScriptedPrompter::new()
+                    .with_answer("Order Service")
+                    .with_answer("Jane")
+                    .with_answer("name    Order Service")
+                    .with_answer("Billing Service")
+                    .with_answer("Accept these answers"),
+            )
+            .build()
+            .unwrap();
+        let report = archetype
+            .execute_script(&archetect, destination.path(), &LinkedHashMap::new())
+            .unwrap();
+        assert_eq!(
+            report.answers().get("slug"),
+            Some(&serde_json::Value::from("billing-service"))
+        );
+        assert_eq!(
+            fs::read_to_string(destination.path().join("billing-service.md")).unwrap(),
+            "# Billing Service by Jane"
+        );
+    }
+
+    #[test]
+    fn test_execute_script_confirm_before_first_write() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path();
+        fs::create_dir_all(root.join("contents")).unwrap();
+        fs::write(root.join("contents/{{ name }}.md"), "# {{ name }}").unwrap();
+        fs::write(
+            root.join("archetype.yml"),
+            indoc!(
+                r#"
+                confirm: true
+                script:
+                  - set:
+                      name:
+                        prompt: "Name:"
+                  - if:
+                      conditions:
+                        - switch-enabled: docs
+                      then:
+                        - print: Skipping the readme
+                      else:
+                        - render:
+                            directory:
+                              source: contents
+                  - set:
+                      author:
+                        prompt: "Author:"
+                "#
+            ),
+        )
+        .unwrap();
+        let archetype = Archetype::from_source(&Source::LocalDirectory { path: root.to_owned() }).unwrap();
+        let destination = tempfile::tempdir().unwrap();
+
+        let archetect = Archetect::builder()
+            .with_prompter(ScriptedPrompter::new().with_answer("readme").with_answer("Abort"))
+            .build()
+            .unwrap();
+        match archetype.execute_script(&archetect, destination.path(), &LinkedHashMap::new()) {
+            Err(ArchetectError::Aborted) => (),
+            _ => panic!("Aborted expected"),
+        }
+        assert!(fs::read_dir(destination.path()).unwrap().next().is_none());
+
+        // The author, set after the render, is taken without review.
+        let archetect = Archetect::builder()
+            .with_prompter(
+                ScriptedPrompter::new()
+                    .with_answer("readme")
+                    .with_answer("Accept these answers")
+                    .with_answer("Jane"),
+            )
+            .build()
+            .unwrap();
+        let report = archetype
+            .execute_script(&archetect, destination.path(), &LinkedHashMap::new())
+            .unwrap();
+        assert_eq!(report.answers().get("author"), Some(&serde_json::Value::from("Jane")));
+        assert!(destination.path().join("readme.md").is_file());
+    }
+
+    #[test]
+    fn test_execute_script_confirm_within_if() {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path();
+        fs::create_dir_all(root.join("contents")).unwrap();
+        fs::write(root.join("contents/{{ name }}.md"), "{{ name }}:{{ port }}").unwrap();
+        fs::write(
+            root.join("archetype.yml"),
+            indoc!(
+                r#"
+                confirm: true
+                script:
+                  - set:
+                      name:
+                        prompt: "Name:"
+                  - if:
+                      conditions:
+                        - equals: ["{{ name }}", "service"]
+                      then:
+                        - set:
+                            port:
+                              prompt: "Port:"
+                              type: int
+                      else:
+                        - set:
+                            port:
+                              value: "0"
+                  - render:
+                      directory:
+                        source: contents
+                "#
+            ),
+        )
+        .unwrap();
+        let archetype = Archetype::from_source(&Source::LocalDirectory { path: root.to_owned() }).unwrap();
+        let destination = tempfile::tempdir().unwrap();
+
+        let archetect = Archetect::builder()
+            .with_prompter(
+                ScriptedPrompter::new()
+                    .with_answer("service")
+                    .with_answer(8080)
+                    .with_answer("port  8080")
+                    .with_answer(9090)
+                    .with_answer("Accept these answers"),
+            )
+            .build()
+            .unwrap();
+        archetype
+            .execute_script(&archetect, destination.path(), &LinkedHashMap::new())
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(destination.path().join("service.md")).unwrap(),
+            "service:9090"
+        );
+    }
 
     #[test]
     fn test_glob_full_directory_path() {
@@ -0,0 +1,545 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+
+use serde_yaml::{Mapping, Value as YamlValue};
+
+use crate::config::{AnswerConfig, AnswerConfigError, AnswerInfo};
+
+/// The text of a YAML answers file, edited a line at a time so that its comments, blank lines and
+/// ordering are kept as they were written.
+///
+/// Block-style mappings are edited in place. An entry that is changed but written some other way,
+/// such as `answers: {}`, is rewritten in block style, along with anything written inside it.
+#[derive(Clone, Debug, Default)]
+pub struct AnswerDocument {
+    lines: Vec<String>,
+}
+
+/// A key within a block-style mapping, along with the lines its value spans.
+struct Entry {
+    key: String,
+    line: usize,
+    end: usize,
+    inline: bool,
+}
+
+impl AnswerDocument {
+    /// Reads an answers file, or starts an empty document if there is none.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<AnswerDocument, AnswerConfigError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(AnswerDocument::default());
+        }
+        Ok(AnswerDocument::parse(&fs::read_to_string(path)?))
+    }
+
+    pub fn parse(text: &str) -> AnswerDocument {
+        AnswerDocument {
+            lines: text.lines().map(|line| line.to_owned()).collect(),
+        }
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), AnswerConfigError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|error| AnswerConfigError::WriteError(error.to_string()))?;
+        }
+        fs::write(path, self.to_string()).map_err(|error| AnswerConfigError::WriteError(error.to_string()))
+    }
+
+    /// Reads the document as it now stands, failing if an edit has left it invalid.
+    pub fn config(&self) -> Result<AnswerConfig, AnswerConfigError> {
+        if !self.lines.iter().any(|line| is_content(line)) {
+            return Ok(AnswerConfig::default());
+        }
+        Ok(serde_yaml::from_str::<AnswerConfig>(&self.to_string())?)
+    }
+
+    /// Sets an answer in `profile`, or in the answers shared by every profile. A dotted identifier, such
+    /// as `db.host`, sets a field of an object's answer.
+    pub fn set_answer(&mut self, profile: Option<&str>, identifier: &str, answer_info: &AnswerInfo) {
+        let mut path = answers_path(profile);
+        let segments = identifier.split('.').collect::<Vec<_>>();
+        if segments.len() == 1 {
+            path.push(identifier);
+            self.set(&path, to_yaml(answer_info));
+        } else {
+            path.push(segments[0]);
+            path.push("value");
+            path.extend(&segments[1..]);
+            self.set(&path, to_yaml(&answer_info.value()));
+        }
+    }
+
+    /// Removes an answer from `profile`, or from the answers shared by every profile, returning whether
+    /// there was one to remove.
+    pub fn unset_answer(&mut self, profile: Option<&str>, identifier: &str) -> bool {
+        let mut path = answers_path(profile);
+        let depth = path.len();
+        let segments = identifier.split('.').collect::<Vec<_>>();
+        path.push(segments[0]);
+        if segments.len() > 1 {
+            path.push("value");
+            path.extend(&segments[1..]);
+        }
+        let end = self.lines.len();
+        let removed = self.remove_in(0, end, &path, 0, depth);
+
+        // Keep an emptied answers mapping, which would otherwise be read as having no value at all.
+        let answers = answers_path(profile);
+        if removed
+            && self
+                .find(&answers)
+                .is_some_and(|entry| entry.end == entry.line + 1 && !entry.inline)
+        {
+            self.set(&answers, YamlValue::Mapping(Mapping::new()));
+        }
+        removed
+    }
+
+    /// Sets the profile `profile` inherits from, creating the profile if it does not exist.
+    pub fn set_profile_inherits(&mut self, profile: &str, inherits: &str) {
+        self.set(&["profiles", profile, "inherits"], YamlValue::from(inherits));
+    }
+
+    /// Sets the profile used when none is selected.
+    pub fn set_default_profile(&mut self, profile: &str) {
+        self.set(&["default-profile"], YamlValue::from(profile));
+    }
+
+    fn set(&mut self, path: &[&str], value: YamlValue) {
+        let end = self.lines.len();
+        self.set_in(0, end, 0, path, value);
+    }
+
+    /// Sets the value at `path` within the mapping spanning lines `start..end`, whose entries are
+    /// indented by `indent` if it has none yet.
+    fn set_in(&mut self, start: usize, end: usize, indent: usize, path: &[&str], value: YamlValue) {
+        let indent = self.mapping_indent(start, end).unwrap_or(indent);
+        let entries = self.entries(start, end, indent);
+        match entries.iter().find(|entry| entry.key == path[0]) {
+            Some(entry) if path.len() == 1 => {
+                self.replace(entry.line, entry.end, render(path[0], value, indent));
+            }
+            Some(entry) if !entry.inline && self.holds_mapping(entry, indent) => {
+                self.set_in(entry.line + 1, entry.end, indent + 2, &path[1..], value);
+            }
+            Some(entry) => {
+                let mut existing = self.entry_value(entry, indent);
+                set_value(&mut existing, &path[1..], value);
+                self.replace(entry.line, entry.end, render(path[0], existing, indent));
+            }
+            None => {
+                let mut nested = YamlValue::Null;
+                set_value(&mut nested, &path[1..], value);
+                let at = entries.last().map_or(start, |entry| entry.end);
+                self.replace(at, at, render(path[0], nested, indent));
+            }
+        }
+    }
+
+    /// Removes the value at `path` within the mapping spanning lines `start..end`. Mappings left empty by
+    /// the removal are removed in turn, down to `keep` levels deep.
+    fn remove_in(&mut self, start: usize, end: usize, path: &[&str], depth: usize, keep: usize) -> bool {
+        let indent = match self.mapping_indent(start, end) {
+            Some(indent) => indent,
+            None => return false,
+        };
+        let entry = match self
+            .entries(start, end, indent)
+            .into_iter()
+            .find(|entry| entry.key == path[0])
+        {
+            Some(entry) => entry,
+            None => return false,
+        };
+        if path.len() == 1 {
+            self.remove_entry(start, &entry, indent);
+            return true;
+        }
+
+        if entry.inline || !self.holds_mapping(&entry, indent) {
+            let mut existing = self.entry_value(&entry, indent);
+            if !remove_value(&mut existing, &path[1..]) {
+                return false;
+            }
+            if depth >= keep && is_empty_mapping(&existing) {
+                self.remove_entry(start, &entry, indent);
+            } else {
+                self.replace(entry.line, entry.end, render(path[0], existing, indent));
+            }
+            return true;
+        }
+
+        let length = self.lines.len();
+        let removed = self.remove_in(entry.line + 1, entry.end, &path[1..], depth + 1, keep);
+        if removed && depth >= keep {
+            let end = end - (length - self.lines.len());
+            if let Some(entry) = self
+                .entries(start, end, indent)
+                .into_iter()
+                .find(|entry| entry.key == path[0])
+            {
+                if entry.end == entry.line + 1 && !entry.inline {
+                    self.remove_entry(start, &entry, indent);
+                }
+            }
+        }
+        removed
+    }
+
+    /// Removes `entry` of the mapping starting at line `start`, along with the comment lines directly
+    /// above it at its indentation.
+    fn remove_entry(&mut self, start: usize, entry: &Entry, indent: usize) {
+        let first = (start..entry.line)
+            .rev()
+            .take_while(|&line| {
+                let line = &self.lines[line];
+                line.trim_start().starts_with('#') && indentation(line) == indent
+            })
+            .last()
+            .unwrap_or(entry.line);
+        self.lines.drain(first..entry.end);
+    }
+
+    fn find(&self, path: &[&str]) -> Option<Entry> {
+        let (mut start, mut end) = (0, self.lines.len());
+        let mut found = None;
+        for key in path {
+            let indent = self.mapping_indent(start, end)?;
+            let entry = self
+                .entries(start, end, indent)
+                .into_iter()
+                .find(|entry| entry.key == *key)?;
+            start = entry.line + 1;
+            end = entry.end;
+            found = Some(entry);
+        }
+        found
+    }
+
+    fn replace(&mut self, start: usize, end: usize, lines: Vec<String>) {
+        self.lines.splice(start..end, lines);
+    }
+
+    /// The indentation of the entries of the mapping spanning lines `start..end`.
+    fn mapping_indent(&self, start: usize, end: usize) -> Option<usize> {
+        self.lines[start..end]
+            .iter()
+            .find(|line| is_content(line))
+            .map(|line| indentation(line))
+    }
+
+    fn entries(&self, start: usize, end: usize, indent: usize) -> Vec<Entry> {
+        let mut entries = vec![];
+        for index in start..end {
+            let line = &self.lines[index];
+            if !is_content(line) || indentation(line) != indent {
+                continue;
+            }
+            if let Some((key, inline)) = parse_key(line) {
+                entries.push(Entry {
+                    key,
+                    line: index,
+                    end: self.block_end(index, end, indent),
+                    inline,
+                });
+            }
+        }
+        entries
+    }
+
+    /// The line after the last line of the value of the key at `line`. Comments and blank lines after
+    /// the value are left to whatever follows it.
+    fn block_end(&self, line: usize, end: usize, indent: usize) -> usize {
+        let mut last = line;
+        for index in line + 1..end {
+            let text = &self.lines[index];
+            if !is_content(text) {
+                continue;
+            }
+            let text_indent = indentation(text);
+            let is_item = text.trim_start().starts_with('-');
+            if text_indent < indent || (text_indent == indent && !is_item) {
+                break;
+            }
+            last = index;
+        }
+        last + 1
+    }
+
+    /// Whether the value of `entry` is a block-style mapping that can be edited in place.
+    fn holds_mapping(&self, entry: &Entry, indent: usize) -> bool {
+        self.lines[entry.line + 1..entry.end]
+            .iter()
+            .find(|line| is_content(line))
+            .is_some_and(|line| indentation(line) > indent && parse_key(line).is_some())
+    }
+
+    fn entry_value(&self, entry: &Entry, indent: usize) -> YamlValue {
+        let text = self.lines[entry.line..entry.end]
+            .iter()
+            .map(|line| line.get(indent..).unwrap_or_else(|| line.trim_start()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        match serde_yaml::from_str::<Mapping>(&text) {
+            Ok(mapping) => mapping
+                .get(&YamlValue::from(entry.key.as_str()))
+                .cloned()
+                .unwrap_or_default(),
+            Err(_) => YamlValue::Null,
+        }
+    }
+}
+
+impl Display for AnswerDocument {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+fn answers_path(profile: Option<&str>) -> Vec<&str> {
+    match profile {
+        Some(profile) => vec!["profiles", profile, "answers"],
+        None => vec!["answers"],
+    }
+}
+
+fn to_yaml<T: serde::Serialize>(value: &T) -> YamlValue {
+    serde_yaml::to_value(value).unwrap_or_default()
+}
+
+/// Renders `key: value` in block style, indented by `indent`.
+fn render(key: &str, value: YamlValue, indent: usize) -> Vec<String> {
+    let mut mapping = Mapping::new();
+    mapping.insert(YamlValue::from(key), value);
+    let yaml = serde_yaml::to_string(&mapping).unwrap_or_default();
+    yaml.lines()
+        .filter(|line| *line != "---")
+        .map(|line| format!("{}{}", " ".repeat(indent), line))
+        .collect()
+}
+
+fn set_value(target: &mut YamlValue, path: &[&str], value: YamlValue) {
+    if path.is_empty() {
+        *target = value;
+        return;
+    }
+    if !target.is_mapping() {
+        *target = YamlValue::Mapping(Mapping::new());
+    }
+    if let YamlValue::Mapping(mapping) = target {
+        let key = YamlValue::from(path[0]);
+        if !mapping.contains_key(&key) {
+            mapping.insert(key.clone(), YamlValue::Null);
+        }
+        if let Some(nested) = mapping.get_mut(&key) {
+            set_value(nested, &path[1..], value);
+        }
+    }
+}
+
+fn remove_value(target: &mut YamlValue, path: &[&str]) -> bool {
+    let mapping = match target {
+        YamlValue::Mapping(mapping) => mapping,
+        _ => return false,
+    };
+    let key = YamlValue::from(path[0]);
+    if path.len() == 1 {
+        return mapping.remove(&key).is_some();
+    }
+    let removed = match mapping.get_mut(&key) {
+        Some(nested) => remove_value(nested, &path[1..]),
+        None => false,
+    };
+    if removed && mapping.get(&key).is_some_and(is_empty_mapping) {
+        mapping.remove(&key);
+    }
+    removed
+}
+
+fn is_empty_mapping(value: &YamlValue) -> bool {
+    value.as_mapping().is_some_and(|mapping| mapping.is_empty())
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Whether a line holds part of the document, rather than being blank, a comment or a document marker.
+fn is_content(line: &str) -> bool {
+    let trimmed = line.trim();
+    !(trimmed.is_empty() || trimmed.starts_with('#') || trimmed == "---" || trimmed == "...")
+}
+
+/// Reads the key of a `key: value` line, and whether the value follows on the same line.
+fn parse_key(line: &str) -> Option<(String, bool)> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with('-') {
+        return None;
+    }
+    let (key, rest) = match trimmed.chars().next()? {
+        quote @ '"' | quote @ '\'' => {
+            let close = trimmed[1..].find(quote)? + 1;
+            let rest = trimmed[close + 1..].strip_prefix(':')?;
+            (trimmed[1..close].to_owned(), rest)
+        }
+        _ => {
+            let colon = trimmed
+                .char_indices()
+                .find(|(index, c)| *c == ':' && trimmed[index + 1..].chars().next().is_none_or(char::is_whitespace))?
+                .0;
+            (trimmed[..colon].trim().to_owned(), &trimmed[colon + 1..])
+        }
+    };
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let rest = rest.trim();
+    Some((key, !rest.is_empty() && !rest.starts_with('#')))
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_set_answer_keeps_comments() {
+        let mut document = AnswerDocument::parse(indoc!(
+            r#"
+            # Shared answers
+            answers:
+              # Who I am
+              author:
+                value: Jane Doe   # full name
+              license:
+                value: MIT
+
+            # Per client
+            profiles:
+              work:
+                answers:
+                  org:
+                    value: acme
+            "#
+        ));
+        document.set_answer(None, "license", &AnswerInfo::with_value("Apache-2.0").build());
+        document.set_answer(None, "port", &AnswerInfo::with_value(8080).build());
+        document.set_answer(Some("work"), "db.host", &AnswerInfo::with_value("localhost").build());
+
+        assert_eq!(
+            document.to_string(),
+            indoc!(
+                r#"
+                # Shared answers
+                answers:
+                  # Who I am
+                  author:
+                    value: Jane Doe   # full name
+                  license:
+                    value: Apache-2.0
+                  port:
+                    value: 8080
+
+                # Per client
+                profiles:
+                  work:
+                    answers:
+                      org:
+                        value: acme
+                      db:
+                        value:
+                          host: localhost
+                "#
+            )
+        );
+        let config = document.config().unwrap();
+        assert_eq!(
+            config.profile_answers(Some("work")).unwrap().get("db").unwrap().value(),
+            Some(&json!({"host": "localhost"}))
+        );
+    }
+
+    #[test]
+    fn test_set_answer_in_new_document() {
+        let mut document = AnswerDocument::parse("answers: {}\n");
+        document.set_answer(
+            None,
+            "stores",
+            &AnswerInfo::with_value(json!(["postgres", "kafka"])).build(),
+        );
+        document.set_answer(Some("work"), "org", &AnswerInfo::with_value("acme").build());
+        document.set_profile_inherits("work", "base");
+        document.set_default_profile("work");
+
+        assert_eq!(
+            document.to_string(),
+            indoc!(
+                r#"
+                answers:
+                  stores:
+                    value:
+                      - postgres
+                      - kafka
+                profiles:
+                  work:
+                    answers:
+                      org:
+                        value: acme
+                    inherits: base
+                default-profile: work
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn test_unset_answer() {
+        let mut document = AnswerDocument::parse(indoc!(
+            r#"
+            answers:
+              author:
+                value: Jane Doe
+              # The database
+              db:
+                value:
+                  host: localhost
+            # Trailing comment
+            "#
+        ));
+        assert!(document.unset_answer(None, "db.host"));
+        assert!(!document.unset_answer(None, "missing"));
+        assert_eq!(
+            document.to_string(),
+            indoc!(
+                r#"
+                answers:
+                  author:
+                    value: Jane Doe
+                # Trailing comment
+                "#
+            )
+        );
+
+        assert!(document.unset_answer(None, "author"));
+        assert!(document.config().unwrap().answers().is_empty());
+        assert!(document.to_string().contains("answers: {}"));
+    }
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(parse_key("  name: value"), Some(("name".to_owned(), true)));
+        assert_eq!(parse_key("name:"), Some(("name".to_owned(), false)));
+        assert_eq!(parse_key("name:   # comment"), Some(("name".to_owned(), false)));
+        assert_eq!(parse_key("\"db.host\": x"), Some(("db.host".to_owned(), true)));
+        assert_eq!(parse_key("url: http://example.com"), Some(("url".to_owned(), true)));
+        assert_eq!(parse_key("- item"), None);
+        assert_eq!(parse_key("http://example.com"), None);
+    }
+}
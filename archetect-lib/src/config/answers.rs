@@ -1,26 +1,104 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use linked_hash_map::LinkedHashMap;
 use log::debug;
 use pest::error::Error as PestError;
 use pest::iterators::Pair;
 use pest::Parser;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use serde_json::{Map, Value};
+
+use crate::input::parse_list;
+
+/// An answer to a variable. Its value may be any structure, such as a list, a number or an object,
+/// and is converted to the variable's type as the variable is set.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+pub struct AnswerInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prompt: Option<String>,
+}
 
-use crate::config::VariableInfo;
-
-pub type AnswerInfo = VariableInfo;
+/// The prefix of environment variables giving answers.
+pub const ANSWER_ENV_PREFIX: &str = "ARCHETECT_ANSWER_";
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct AnswerConfig {
-    #[serde(skip_serializing_if = "LinkedHashMap::is_empty")]
+    #[serde(
+        default,
+        deserialize_with = "deserialize_answers",
+        skip_serializing_if = "LinkedHashMap::is_empty"
+    )]
     answers: LinkedHashMap<String, AnswerInfo>,
+    #[serde(default, skip_serializing_if = "LinkedHashMap::is_empty")]
+    profiles: LinkedHashMap<String, AnswerProfile>,
+    #[serde(rename = "default-profile", skip_serializing_if = "Option::is_none")]
+    default_profile: Option<String>,
+}
+
+/// A named set of answers, applied over those of the profile it inherits from, or over the answers
+/// shared by every profile.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct AnswerProfile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inherits: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_answers",
+        skip_serializing_if = "LinkedHashMap::is_empty"
+    )]
+    answers: LinkedHashMap<String, AnswerInfo>,
+}
+
+impl AnswerProfile {
+    pub fn inherits(&self) -> Option<&str> {
+        self.inherits.as_deref()
+    }
+
+    pub fn answers(&self) -> &LinkedHashMap<String, AnswerInfo> {
+        &self.answers
+    }
+}
+
+/// Reads answers, merging those with dotted identifiers into the objects they answer fields of.
+fn deserialize_answers<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<LinkedHashMap<String, AnswerInfo>, D::Error> {
+    let mut answers = LinkedHashMap::new();
+    for (identifier, answer_info) in LinkedHashMap::<String, AnswerInfo>::deserialize(deserializer)? {
+        insert_answer(&mut answers, &identifier, answer_info).map_err(|error| match error {
+            AnswerConfigError::ConflictingAnswer(identifier) => D::Error::custom(format!(
+                "'{}' answers a field of a variable that is not answered with an object",
+                identifier
+            )),
+            AnswerConfigError::FieldWithoutValue(identifier) => D::Error::custom(format!(
+                "'{}' answers a field, and must give a value rather than only a default",
+                identifier
+            )),
+            error => D::Error::custom(format!("{:?}", error)),
+        })?;
+    }
+    Ok(answers)
 }
 
 #[derive(Debug, PartialEq)]
 pub enum AnswerConfigError {
     ParseError(String),
     MissingError,
+    WriteError(String),
+    MissingProfile(String),
+    CyclicProfile(String),
+    MissingAnswer(String),
+    /// A dotted answer, such as `db.host`, for a variable, or field, already answered with something
+    /// other than an object.
+    ConflictingAnswer(String),
+    /// A dotted answer giving only a default, which cannot be merged into the object it answers a field of.
+    FieldWithoutValue(String),
 }
 
 impl From<serde_yaml::Error> for AnswerConfigError {
@@ -29,6 +107,18 @@ impl From<serde_yaml::Error> for AnswerConfigError {
     }
 }
 
+impl From<serde_json::Error> for AnswerConfigError {
+    fn from(error: serde_json::Error) -> Self {
+        AnswerConfigError::ParseError(error.to_string())
+    }
+}
+
+impl From<toml::de::Error> for AnswerConfigError {
+    fn from(error: toml::de::Error) -> Self {
+        AnswerConfigError::ParseError(error.to_string())
+    }
+}
+
 impl From<std::io::Error> for AnswerConfigError {
     fn from(_: std::io::Error) -> Self {
         // TODO: Distinguish between missing and other errors
@@ -40,33 +130,89 @@ impl AnswerConfig {
     pub fn load<P: Into<PathBuf>>(path: P) -> Result<AnswerConfig, AnswerConfigError> {
         let path = path.into();
         if path.is_dir() {
-            let answer_file_names = vec![
-                "archetect.yml",
-                ".archetect.yml",
-                "archetect.yaml",
-                ".archetect.yaml",
-                ".answers.yaml",
-                "answers.yaml",
-            ];
-            for answer_file_name in answer_file_names {
-                let answers = path.join(answer_file_name);
-                if answers.exists() {
-                    debug!("Reading Archetect config from '{}'", &answers.display());
-                    let config = fs::read_to_string(answers)?;
-                    let config = serde_yaml::from_str::<AnswerConfig>(&config)?;
-                    return Ok(config);
-                }
+            if let Some(answers) = AnswerConfig::find(&path) {
+                debug!("Reading Archetect config from '{}'", &answers.display());
+                return AnswerConfig::load_file(&answers);
             }
         } else {
-            let config = fs::read_to_string(path)?;
-            let config = serde_yaml::from_str::<AnswerConfig>(&config)?;
-            return Ok(config);
+            return AnswerConfig::load_file(&path);
         }
 
         // TODO: Return Ok(None) instead of error
         Err(AnswerConfigError::MissingError)
     }
 
+    /// The answers file in `directory`, such as a project's `.archetect.yml`, if it has one.
+    pub fn find<P: AsRef<Path>>(directory: P) -> Option<PathBuf> {
+        let answer_file_names = vec![
+            "archetect.yml",
+            ".archetect.yml",
+            "archetect.yaml",
+            ".archetect.yaml",
+            ".answers.yaml",
+            "answers.yaml",
+            ".answers.json",
+            "answers.json",
+            ".answers.toml",
+            "answers.toml",
+        ];
+        answer_file_names
+            .into_iter()
+            .map(|answer_file_name| directory.as_ref().join(answer_file_name))
+            .find(|answers| answers.exists())
+    }
+
+    /// Reads an answer file as JSON or TOML when it has that extension, and as YAML otherwise.
+    fn load_file(path: &Path) -> Result<AnswerConfig, AnswerConfigError> {
+        let config = fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Ok(serde_json::from_str::<AnswerConfig>(&config)?),
+            Some("toml") => Ok(toml::from_str::<AnswerConfig>(&config)?),
+            _ => Ok(serde_yaml::from_str::<AnswerConfig>(&config)?),
+        }
+    }
+
+    /// Reads answers of an unknown format, such as those piped to stdin, as YAML or JSON, and failing
+    /// that, as TOML.
+    pub fn parse(contents: &str) -> Result<AnswerConfig, AnswerConfigError> {
+        match serde_yaml::from_str::<AnswerConfig>(contents) {
+            Ok(config) => Ok(config),
+            Err(error) => toml::from_str::<AnswerConfig>(contents).map_err(|_| error.into()),
+        }
+    }
+
+    /// Reads answers from `ARCHETECT_ANSWER_<NAME>` environment variables. Names are lowercased, and a
+    /// double underscore stands for a dot, so `ARCHETECT_ANSWER_DB__HOST` answers `db.host`.
+    pub fn from_env() -> Result<AnswerConfig, AnswerConfigError> {
+        AnswerConfig::from_vars(std::env::vars())
+    }
+
+    fn from_vars<I: IntoIterator<Item = (String, String)>>(vars: I) -> Result<AnswerConfig, AnswerConfigError> {
+        let mut answers = LinkedHashMap::new();
+        for (name, value) in vars {
+            if let Some(identifier) = name.strip_prefix(ANSWER_ENV_PREFIX) {
+                if identifier.is_empty() {
+                    continue;
+                }
+                let identifier = identifier.to_lowercase().replace("__", ".");
+                insert_answer(&mut answers, &identifier, AnswerInfo::with_value(value).build())?;
+            }
+        }
+        Ok(AnswerConfig {
+            answers,
+            ..Default::default()
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), AnswerConfigError> {
+        let path = path.as_ref();
+        let yaml = serde_yaml::to_string(self)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|error| AnswerConfigError::WriteError(error.to_string()))?;
+        }
+        fs::write(path, yaml).map_err(|error| AnswerConfigError::WriteError(error.to_string()))
+    }
+
     pub fn add_answer(&mut self, identifier: &str, value: &str) {
         self.answers
             .insert(identifier.to_owned(), AnswerInfo::with_value(value).build());
@@ -80,12 +226,194 @@ impl AnswerConfig {
     pub fn answers(&self) -> &LinkedHashMap<String, AnswerInfo> {
         &self.answers
     }
+
+    pub fn profiles(&self) -> &LinkedHashMap<String, AnswerProfile> {
+        &self.profiles
+    }
+
+    /// The profile used when none is selected.
+    pub fn default_profile(&self) -> Option<&str> {
+        self.default_profile.as_deref()
+    }
+
+    pub fn set_default_profile(&mut self, profile: Option<String>) {
+        self.default_profile = profile;
+    }
+
+    /// Sets the profile `profile` inherits from, creating the profile if it does not exist.
+    pub fn set_profile_inherits(&mut self, profile: &str, inherits: Option<String>) {
+        self.profiles.entry(profile.to_owned()).or_default().inherits = inherits;
+    }
+
+    /// Adds an answer to `profile`, creating the profile if it does not exist, or to the shared answers
+    /// when no profile is given.
+    pub fn set_answer(
+        &mut self,
+        profile: Option<&str>,
+        identifier: &str,
+        answer_info: AnswerInfo,
+    ) -> Result<(), AnswerConfigError> {
+        let answers = match profile {
+            Some(profile) => &mut self.profiles.entry(profile.to_owned()).or_default().answers,
+            None => &mut self.answers,
+        };
+        insert_answer(answers, identifier, answer_info)
+    }
+
+    /// The answers given by `profile`, or by the default profile when none is given, over those it
+    /// inherits and those shared by every profile.
+    pub fn profile_answers(
+        &self,
+        profile: Option<&str>,
+    ) -> Result<LinkedHashMap<String, AnswerInfo>, AnswerConfigError> {
+        let mut lineage = vec![];
+        let mut next = profile.or_else(|| self.default_profile());
+        while let Some(name) = next {
+            if lineage.contains(&name) {
+                return Err(AnswerConfigError::CyclicProfile(name.to_owned()));
+            }
+            let profile = self
+                .profiles
+                .get(name)
+                .ok_or_else(|| AnswerConfigError::MissingProfile(name.to_owned()))?;
+            lineage.push(name);
+            next = profile.inherits();
+        }
+
+        let mut answers = self.answers.clone();
+        for name in lineage.iter().rev() {
+            for (identifier, answer_info) in self.profiles[*name].answers() {
+                insert_answer(&mut answers, identifier, answer_info.clone())?;
+            }
+        }
+        Ok(answers)
+    }
+}
+
+impl AnswerInfo {
+    pub fn new() -> AnswerInfoBuilder {
+        AnswerInfoBuilder {
+            answer_info: AnswerInfo {
+                value: None,
+                default: None,
+                prompt: None,
+            },
+        }
+    }
+
+    pub fn with_value<V: Into<Value>>(value: V) -> AnswerInfoBuilder {
+        AnswerInfo::new().with_value(value)
+    }
+
+    pub fn with_default<D: Into<Value>>(default: D) -> AnswerInfoBuilder {
+        AnswerInfo::new().with_default(default)
+    }
+
+    pub fn with_prompt<P: Into<String>>(prompt: P) -> AnswerInfoBuilder {
+        AnswerInfo::new().with_prompt(prompt)
+    }
+
+    pub fn value(&self) -> Option<&Value> {
+        self.value.as_ref()
+    }
+
+    pub fn default(&self) -> Option<&Value> {
+        self.default.as_ref()
+    }
+
+    pub fn prompt(&self) -> Option<&str> {
+        self.prompt.as_deref()
+    }
+
+    pub fn parse(input: &str) -> Result<(String, AnswerInfo), AnswerParseError> {
+        parse(input)
+    }
+}
+
+pub struct AnswerInfoBuilder {
+    answer_info: AnswerInfo,
+}
+
+impl AnswerInfoBuilder {
+    pub fn with_value<V: Into<Value>>(mut self, value: V) -> AnswerInfoBuilder {
+        self.answer_info.value = Some(value.into());
+        self
+    }
+
+    pub fn with_default<D: Into<Value>>(mut self, default: D) -> AnswerInfoBuilder {
+        self.answer_info.default = Some(default.into());
+        self
+    }
+
+    pub fn with_prompt<P: Into<String>>(mut self, prompt: P) -> AnswerInfoBuilder {
+        self.answer_info.prompt = Some(prompt.into());
+        self
+    }
+
+    pub fn build(self) -> AnswerInfo {
+        self.answer_info
+    }
+}
+
+/// Adds an answer to `answers`, replacing any answer to the same variable. A dotted identifier, such as
+/// `db.host`, answers a field of an object, and is merged into any answer already given to the object.
+/// Such answers must give a value, and cannot answer a field of something answered other than with an
+/// object.
+pub fn insert_answer(
+    answers: &mut LinkedHashMap<String, AnswerInfo>,
+    identifier: &str,
+    answer_info: AnswerInfo,
+) -> Result<(), AnswerConfigError> {
+    let mut segments = identifier.splitn(2, '.');
+    let root = segments.next().unwrap_or_default();
+    let field = match segments.next() {
+        Some(field) => field,
+        None => {
+            answers.insert(identifier.to_owned(), answer_info);
+            return Ok(());
+        }
+    };
+    let value = answer_info
+        .value
+        .ok_or_else(|| AnswerConfigError::FieldWithoutValue(identifier.to_owned()))?;
+
+    // The object keeps any default or prompt it was given.
+    let mut root_answer = answers.get(root).cloned().unwrap_or_else(|| AnswerInfo::new().build());
+    let mut object = match root_answer.value.take() {
+        Some(Value::Object(object)) => object,
+        Some(_) => return Err(AnswerConfigError::ConflictingAnswer(identifier.to_owned())),
+        None => Map::new(),
+    };
+    insert_field(&mut object, identifier, field, value)?;
+    root_answer.value = Some(Value::Object(object));
+    answers.insert(root.to_owned(), root_answer);
+    Ok(())
+}
+
+fn insert_field(
+    object: &mut Map<String, Value>,
+    identifier: &str,
+    path: &str,
+    value: Value,
+) -> Result<(), AnswerConfigError> {
+    match path.split_once('.') {
+        Some((field, rest)) => match object.entry(field).or_insert_with(|| Value::Object(Map::new())) {
+            Value::Object(nested) => insert_field(nested, identifier, rest, value),
+            _ => Err(AnswerConfigError::ConflictingAnswer(identifier.to_owned())),
+        },
+        None => {
+            object.insert(path.to_owned(), value);
+            Ok(())
+        }
+    }
 }
 
 impl Default for AnswerConfig {
     fn default() -> Self {
         AnswerConfig {
             answers: LinkedHashMap::new(),
+            profiles: LinkedHashMap::new(),
+            default_profile: None,
         }
     }
 }
@@ -97,6 +425,7 @@ struct AnswerParser;
 #[derive(Debug, PartialEq)]
 pub enum AnswerParseError {
     PestError(PestError<Rule>),
+    InvalidJson(String),
 }
 
 impl From<PestError<Rule>> for AnswerParseError {
@@ -107,18 +436,21 @@ impl From<PestError<Rule>> for AnswerParseError {
 
 fn parse(source: &str) -> Result<(String, AnswerInfo), AnswerParseError> {
     let mut pairs = AnswerParser::parse(Rule::answer, source)?;
-    Ok(parse_answer(pairs.next().unwrap()))
+    parse_answer(pairs.next().unwrap())
 }
 
-fn parse_answer(pair: Pair<Rule>) -> (String, AnswerInfo) {
+fn parse_answer(pair: Pair<Rule>) -> Result<(String, AnswerInfo), AnswerParseError> {
     assert_eq!(pair.as_rule(), Rule::answer);
     let mut iter = pair.into_inner();
     let identifier_pair = iter.next().unwrap();
     let value_pair = iter.next().unwrap();
-    (
-        parse_identifier(identifier_pair),
-        AnswerInfo::with_value(parse_value(value_pair)).build(),
-    )
+    let value = match value_pair.as_rule() {
+        Rule::json => serde_json::from_str::<Value>(value_pair.as_str())
+            .map_err(|error| AnswerParseError::InvalidJson(error.to_string()))?,
+        Rule::list => Value::from(parse_list(value_pair.as_str())),
+        _ => Value::String(parse_value(value_pair)),
+    };
+    Ok((parse_identifier(identifier_pair), AnswerInfo::with_value(value).build()))
 }
 
 fn parse_identifier(pair: Pair<Rule>) -> String {
@@ -131,16 +463,284 @@ fn parse_value(pair: Pair<Rule>) -> String {
     pair.into_inner().next().unwrap().as_str().to_owned()
 }
 
-impl AnswerInfo {
-    pub fn parse(input: &str) -> Result<(String, AnswerInfo), AnswerParseError> {
-        parse(input)
-    }
-}
-
 #[cfg(test)]
 mod tests {
+    use indoc::indoc;
+
     use super::*;
 
+    #[test]
+    fn test_deserialize_typed_values() {
+        let config: AnswerConfig = serde_yaml::from_str(
+            r#"
+            answers:
+              stores:
+                value: [postgres, kafka]
+              name:
+                value: example
+              port:
+                value: 8080
+              tls:
+                value: true
+              db:
+                value:
+                  host: localhost
+            "#,
+        )
+        .unwrap();
+        let value = |identifier: &str| config.answers().get(identifier).unwrap().value().cloned();
+        assert_eq!(value("stores"), Some(json!(["postgres", "kafka"])));
+        assert_eq!(value("name"), Some(json!("example")));
+        assert_eq!(value("port"), Some(json!(8080)));
+        assert_eq!(value("tls"), Some(json!(true)));
+        assert_eq!(value("db"), Some(json!({"host": "localhost"})));
+    }
+
+    #[test]
+    fn test_load_formats() {
+        let directory = tempfile::tempdir().unwrap();
+        let json = directory.path().join("answers.json");
+        fs::write(&json, r#"{"answers": {"port": {"value": 8080}}}"#).unwrap();
+        let toml = directory.path().join("answers.toml");
+        fs::write(&toml, "[answers.stores]\nvalue = [\"postgres\", \"kafka\"]\n").unwrap();
+
+        let config = AnswerConfig::load(&json).unwrap();
+        assert_eq!(config.answers().get("port").unwrap().value(), Some(&json!(8080)));
+        let config = AnswerConfig::load(&toml).unwrap();
+        assert_eq!(
+            config.answers().get("stores").unwrap().value(),
+            Some(&json!(["postgres", "kafka"]))
+        );
+
+        fs::write(&json, "answers:\n  port:\n    value: 8080\n").unwrap();
+        match AnswerConfig::load(&json) {
+            Err(AnswerConfigError::ParseError(_)) => (),
+            _ => panic!("Error expected"),
+        }
+    }
+
+    #[test]
+    fn test_parse_unknown_format() {
+        let config = AnswerConfig::parse(r#"{"answers": {"name": {"value": "example"}}}"#).unwrap();
+        assert_eq!(config.answers().get("name").unwrap().value(), Some(&json!("example")));
+
+        let config = AnswerConfig::parse("[answers.name]\nvalue = \"example\"\n").unwrap();
+        assert_eq!(config.answers().get("name").unwrap().value(), Some(&json!("example")));
+
+        assert!(AnswerConfig::parse("answers: [").is_err());
+    }
+
+    #[test]
+    fn test_profile_answers() {
+        let config: AnswerConfig = serde_yaml::from_str(indoc!(
+            r#"
+            answers:
+              author:
+                value: Jane Doe
+              license:
+                value: MIT
+            default-profile: personal
+            profiles:
+              personal:
+                answers:
+                  org:
+                    value: jane
+              work:
+                answers:
+                  license:
+                    value: Proprietary
+                  org:
+                    value: acme
+              client:
+                inherits: work
+                answers:
+                  org:
+                    value: acme.client
+            "#
+        ))
+        .unwrap();
+
+        let answers = config.profile_answers(Some("client")).unwrap();
+        let value = |identifier: &str| answers.get(identifier).unwrap().value().cloned();
+        assert_eq!(value("author"), Some(json!("Jane Doe")));
+        assert_eq!(value("license"), Some(json!("Proprietary")));
+        assert_eq!(value("org"), Some(json!("acme.client")));
+
+        let answers = config.profile_answers(None).unwrap();
+        assert_eq!(answers.get("org").unwrap().value(), Some(&json!("jane")));
+        assert_eq!(answers.get("license").unwrap().value(), Some(&json!("MIT")));
+
+        assert_eq!(
+            config.profile_answers(Some("missing")),
+            Err(AnswerConfigError::MissingProfile("missing".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_profile_cycle() {
+        let mut config = AnswerConfig::default();
+        config
+            .set_answer(Some("a"), "org", AnswerInfo::with_value("a").build())
+            .unwrap();
+        config.set_profile_inherits("a", Some("b".to_owned()));
+        config.set_profile_inherits("b", Some("a".to_owned()));
+        assert_eq!(
+            config.profile_answers(Some("a")),
+            Err(AnswerConfigError::CyclicProfile("a".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_from_vars() {
+        let config = AnswerConfig::from_vars(vec![
+            ("ARCHETECT_ANSWER_PROJECT_NAME".to_owned(), "orders".to_owned()),
+            ("ARCHETECT_ANSWER_DB__HOST".to_owned(), "localhost".to_owned()),
+            ("ARCHETECT_ANSWER_".to_owned(), "ignored".to_owned()),
+            ("HOME".to_owned(), "/home/jane".to_owned()),
+        ])
+        .unwrap();
+        assert_eq!(config.answers().len(), 2);
+        assert_eq!(
+            config.answers().get("project_name").unwrap().value(),
+            Some(&json!("orders"))
+        );
+        assert_eq!(
+            config.answers().get("db").unwrap().value(),
+            Some(&json!({"host": "localhost"}))
+        );
+    }
+
+    #[test]
+    fn test_parse_typed_values() {
+        assert_eq!(
+            parse("stores=[postgres, kafka]"),
+            Ok((
+                "stores".to_owned(),
+                AnswerInfo::with_value(json!(["postgres", "kafka"])).build()
+            ))
+        );
+
+        assert_eq!(
+            parse("stores=[ 'postgres', \"kafka, streams\" ]"),
+            Ok((
+                "stores".to_owned(),
+                AnswerInfo::with_value(json!(["postgres", "kafka, streams"])).build()
+            ))
+        );
+
+        assert_eq!(
+            parse("stores=[]"),
+            Ok(("stores".to_owned(), AnswerInfo::with_value(json!([])).build()))
+        );
+
+        assert_eq!(
+            parse("title=[WIP] fix"),
+            Ok(("title".to_owned(), AnswerInfo::with_value("[WIP] fix").build()))
+        );
+
+        assert_eq!(
+            parse("stores='[postgres, kafka]'"),
+            Ok(("stores".to_owned(), AnswerInfo::with_value("[postgres, kafka]").build()))
+        );
+
+        assert_eq!(
+            parse(r#"db:={"host": "localhost", "port": 5432}"#),
+            Ok((
+                "db".to_owned(),
+                AnswerInfo::with_value(json!({"host": "localhost", "port": 5432})).build()
+            ))
+        );
+
+        assert_eq!(
+            parse("port := 8080"),
+            Ok(("port".to_owned(), AnswerInfo::with_value(8080).build()))
+        );
+
+        assert_eq!(
+            parse("db.host=localhost"),
+            Ok(("db.host".to_owned(), AnswerInfo::with_value("localhost").build()))
+        );
+
+        match parse("db:={host}") {
+            Err(AnswerParseError::InvalidJson(_)) => (),
+            _ => panic!("Error expected"),
+        }
+    }
+
+    #[test]
+    fn test_insert_answer() {
+        let mut answers = LinkedHashMap::new();
+        insert_answer(
+            &mut answers,
+            "db",
+            AnswerInfo::with_value(json!({"host": "db", "port": 5432})).build(),
+        )
+        .unwrap();
+        insert_answer(&mut answers, "db.host", AnswerInfo::with_value("localhost").build()).unwrap();
+        insert_answer(&mut answers, "db.pool.size", AnswerInfo::with_value(10).build()).unwrap();
+        insert_answer(&mut answers, "name", AnswerInfo::with_value("example").build()).unwrap();
+
+        assert_eq!(
+            answers.get("db").unwrap().value(),
+            Some(&json!({"host": "localhost", "port": 5432, "pool": {"size": 10}}))
+        );
+        assert_eq!(answers.get("name").unwrap().value(), Some(&json!("example")));
+    }
+
+    #[test]
+    fn test_insert_answer_default_only_field() {
+        let mut answers = LinkedHashMap::new();
+        assert_eq!(
+            insert_answer(&mut answers, "db.host", AnswerInfo::with_default("localhost").build()),
+            Err(AnswerConfigError::FieldWithoutValue("db.host".to_owned()))
+        );
+        assert!(answers.is_empty());
+
+        let result: Result<AnswerConfig, _> = serde_yaml::from_str(indoc!(
+            r#"
+            answers:
+              db.host:
+                default: localhost
+            "#
+        ));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_insert_answer_conflicting_root() {
+        let mut answers = LinkedHashMap::new();
+        insert_answer(&mut answers, "db", AnswerInfo::with_value("x").build()).unwrap();
+        assert_eq!(
+            insert_answer(&mut answers, "db.host", AnswerInfo::with_value("y").build()),
+            Err(AnswerConfigError::ConflictingAnswer("db.host".to_owned()))
+        );
+        assert_eq!(answers.get("db").unwrap().value(), Some(&json!("x")));
+
+        insert_answer(&mut answers, "server.host", AnswerInfo::with_value("localhost").build()).unwrap();
+        assert_eq!(
+            insert_answer(&mut answers, "server.host.port", AnswerInfo::with_value(8080).build()),
+            Err(AnswerConfigError::ConflictingAnswer("server.host.port".to_owned()))
+        );
+        assert_eq!(
+            answers.get("server").unwrap().value(),
+            Some(&json!({"host": "localhost"}))
+        );
+    }
+
+    #[test]
+    fn test_insert_answer_keeps_root_default() {
+        let mut answers = LinkedHashMap::new();
+        insert_answer(
+            &mut answers,
+            "db",
+            AnswerInfo::with_default(json!({"host": "db"})).build(),
+        )
+        .unwrap();
+        insert_answer(&mut answers, "db.port", AnswerInfo::with_value(5432).build()).unwrap();
+        assert_eq!(answers.get("db").unwrap().value(), Some(&json!({"port": 5432})));
+        assert_eq!(answers.get("db").unwrap().default(), Some(&json!({"host": "db"})));
+    }
+
     #[test]
     fn test_parse_success() {
         assert_eq!(
@@ -216,7 +816,7 @@ mod tests {
     fn test_parse_answer() {
         assert_eq!(
             parse_answer(AnswerParser::parse(Rule::answer, "key=value").unwrap().next().unwrap()),
-            ("key".to_owned(), AnswerInfo::with_value("value").build())
+            Ok(("key".to_owned(), AnswerInfo::with_value("value").build()))
         );
 
         assert_eq!(
@@ -226,7 +826,7 @@ mod tests {
                     .next()
                     .unwrap()
             ),
-            ("key".to_owned(), AnswerInfo::with_value("value").build())
+            Ok(("key".to_owned(), AnswerInfo::with_value("value").build()))
         );
 
         assert_eq!(
@@ -236,7 +836,7 @@ mod tests {
                     .next()
                     .unwrap()
             ),
-            ("key".to_owned(), AnswerInfo::with_value("value").build())
+            Ok(("key".to_owned(), AnswerInfo::with_value("value").build()))
         );
     }
 
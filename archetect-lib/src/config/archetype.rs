@@ -3,6 +3,9 @@ use crate::ArchetypeError;
 use std::fs;
 use std::path::PathBuf;
 
+/// The template suffixes used when an archetype opts into suffix mode without naming its own.
+pub const DEFAULT_TEMPLATE_SUFFIXES: &[&str] = &[".tera", ".j2"];
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ArchetypeConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -15,8 +18,14 @@ pub struct ArchetypeConfig {
     frameworks: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    templates: Option<String>,
+    #[serde(rename = "template-suffixes", skip_serializing_if = "Option::is_none")]
+    template_suffixes: Option<TemplateSuffixes>,
     #[serde(skip_serializing_if = "Option::is_none", alias = "actions")]
     script: Option<Vec<ActionId>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    confirm: Option<bool>,
 }
 
 impl ArchetypeConfig {
@@ -51,6 +60,10 @@ impl ArchetypeConfig {
         self
     }
 
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
     pub fn add_author(&mut self, author: &str) {
         let authors = self.authors.get_or_insert_with(|| vec![]);
         authors.push(author.into());
@@ -107,6 +120,40 @@ impl ArchetypeConfig {
         self.frameworks.as_ref().map(|r| r.as_slice()).unwrap_or_default()
     }
 
+    pub fn with_templates(mut self, templates: &str) -> ArchetypeConfig {
+        self.templates = Some(templates.to_owned());
+        self
+    }
+
+    /// The directory, relative to the archetype, holding templates that content files may include,
+    /// import macros from, or extend.
+    pub fn templates(&self) -> Option<&str> {
+        self.templates.as_deref()
+    }
+
+    pub fn with_template_suffixes(mut self, suffixes: &[&str]) -> ArchetypeConfig {
+        let suffixes = suffixes.iter().map(|suffix| (*suffix).to_owned()).collect();
+        self.template_suffixes = Some(TemplateSuffixes::Custom(suffixes));
+        self
+    }
+
+    /// The suffixes marking content files as templates, if the archetype opted into suffix mode. In
+    /// that mode only files ending in one of them are rendered, with the suffix stripped from their
+    /// destination name, and every other file is copied as is. Template files that look binary are
+    /// copied as well, unless a RENDER source rule matches them.
+    pub fn template_suffixes(&self) -> Option<Vec<String>> {
+        match &self.template_suffixes {
+            Some(TemplateSuffixes::Enabled(true)) => Some(
+                DEFAULT_TEMPLATE_SUFFIXES
+                    .iter()
+                    .map(|suffix| (*suffix).to_owned())
+                    .collect(),
+            ),
+            Some(TemplateSuffixes::Custom(suffixes)) => Some(suffixes.clone()),
+            Some(TemplateSuffixes::Enabled(false)) | None => None,
+        }
+    }
+
     pub fn with_action(mut self, action: ActionId) -> ArchetypeConfig {
         self.add_action(action);
         self
@@ -120,6 +167,16 @@ impl ArchetypeConfig {
     pub fn actions(&self) -> &[ActionId] {
         self.script.as_ref().map(|r| r.as_slice()).unwrap_or_default()
     }
+
+    pub fn with_confirm(mut self, confirm: bool) -> ArchetypeConfig {
+        self.confirm = Some(confirm);
+        self
+    }
+
+    /// Whether the answers given to the script's top-level `set` actions are reviewed before it goes on.
+    pub fn confirm(&self) -> bool {
+        self.confirm.unwrap_or_default()
+    }
 }
 
 impl Default for ArchetypeConfig {
@@ -130,7 +187,10 @@ impl Default for ArchetypeConfig {
             languages: None,
             frameworks: None,
             tags: None,
+            templates: None,
+            template_suffixes: None,
             script: None,
+            confirm: None,
         }
     }
 }
@@ -159,9 +219,18 @@ mod tests {
             .with_framework("Hessian")
             .with_tag("Service")
             .with_tag("REST")
+            .with_templates("templates")
             .with_action(ActionId::Set(variables));
 
         let output = serde_yaml::to_string(&config).unwrap();
         println!("{}", output);
     }
 }
+
+/// Either `true`, to render files ending in the default template suffixes, or a list of suffixes.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+enum TemplateSuffixes {
+    Enabled(bool),
+    Custom(Vec<String>),
+}
@@ -1,3 +1,4 @@
+use crate::input::PromptError;
 use crate::util::{Source, SourceError};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -80,6 +81,7 @@ pub enum CatalogError {
     NotFound(PathBuf),
     IOError(std::io::Error),
     YamlError(serde_yaml::Error),
+    PromptError(PromptError),
 }
 
 impl From<std::io::Error> for CatalogError {
@@ -88,6 +90,12 @@ impl From<std::io::Error> for CatalogError {
     }
 }
 
+impl From<PromptError> for CatalogError {
+    fn from(e: PromptError) -> Self {
+        CatalogError::PromptError(e)
+    }
+}
+
 impl From<serde_yaml::Error> for CatalogError {
     fn from(e: serde_yaml::Error) -> Self {
         CatalogError::YamlError(e)
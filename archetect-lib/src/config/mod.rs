@@ -1,13 +1,20 @@
+mod answer_document;
 mod answers;
 mod archetype;
 mod catalog;
 mod catalog2;
 mod rule;
+mod validation;
 mod variable;
 
-pub use answers::{AnswerConfig, AnswerConfigError, AnswerInfo};
-pub use archetype::ArchetypeConfig;
+pub use answer_document::AnswerDocument;
+pub use answers::{
+    insert_answer, AnswerConfig, AnswerConfigError, AnswerInfo, AnswerInfoBuilder, AnswerParseError, AnswerProfile,
+    ANSWER_ENV_PREFIX,
+};
+pub use archetype::{ArchetypeConfig, DEFAULT_TEMPLATE_SUFFIXES};
 pub use catalog::{CatalogConfig, CatalogConfigEntry, CatalogConfigEntryType, CatalogConfigError};
 pub use catalog2::{Catalog, CatalogEntry, CatalogError, CATALOG_FILE_NAME};
 pub use rule::{Pattern, RuleAction, RuleConfig};
+pub use validation::Validation;
 pub use variable::{VariableInfo, VariableInfoBuilder, VariableType};
@@ -73,8 +73,14 @@ pub enum Pattern {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum RuleAction {
+    /// Copies matching files as they are.
     COPY,
+    /// Renders matching files as templates. Files without a matching rule are rendered too, unless
+    /// their content looks binary, in which case they are copied; a RENDER rule renders them anyway.
+    /// The same goes for template files in suffix mode, where files without a template suffix are
+    /// copied unless a rule says otherwise.
     RENDER,
+    /// Leaves matching files out.
     SKIP,
 }
 
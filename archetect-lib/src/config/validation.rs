@@ -0,0 +1,330 @@
+use std::path::Path;
+
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+/// Constraints an answer must satisfy, beyond being of the variable's type.
+///
+/// String rules apply to strings and to every item of a list. `min` and `max` bound integers, and the
+/// number of items in lists.
+/// The `regex` need only match part of a value; anchor it with `^` and `$` to match all of it.
+/// `allowed-chars` lists the characters a value may contain, where `a-z` stands for a range, and
+/// `exists` requires a path to exist.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Validation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    regex: Option<RegexPattern>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max: Option<i64>,
+    #[serde(rename = "min-length", skip_serializing_if = "Option::is_none")]
+    min_length: Option<usize>,
+    #[serde(rename = "max-length", skip_serializing_if = "Option::is_none")]
+    max_length: Option<usize>,
+    #[serde(rename = "allowed-chars", skip_serializing_if = "Option::is_none")]
+    allowed_chars: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exists: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+impl Validation {
+    pub fn new() -> Validation {
+        Validation::default()
+    }
+
+    pub fn with_regex(mut self, regex: &str) -> Result<Validation, regex::Error> {
+        self.regex = Some(RegexPattern::new(regex)?);
+        Ok(self)
+    }
+
+    pub fn with_min(mut self, min: i64) -> Validation {
+        self.min = Some(min);
+        self
+    }
+
+    pub fn with_max(mut self, max: i64) -> Validation {
+        self.max = Some(max);
+        self
+    }
+
+    pub fn with_min_length(mut self, min_length: usize) -> Validation {
+        self.min_length = Some(min_length);
+        self
+    }
+
+    pub fn with_max_length(mut self, max_length: usize) -> Validation {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    pub fn with_allowed_chars<C: Into<String>>(mut self, allowed_chars: C) -> Validation {
+        self.allowed_chars = Some(allowed_chars.into());
+        self
+    }
+
+    pub fn with_exists(mut self, exists: bool) -> Validation {
+        self.exists = Some(exists);
+        self
+    }
+
+    pub fn with_message<M: Into<String>>(mut self, message: M) -> Validation {
+        self.message = Some(message.into());
+        self
+    }
+
+    pub fn regex(&self) -> Option<&str> {
+        self.regex.as_ref().map(|regex| regex.source.as_str())
+    }
+
+    pub fn min(&self) -> Option<i64> {
+        self.min
+    }
+
+    pub fn max(&self) -> Option<i64> {
+        self.max
+    }
+
+    pub fn min_length(&self) -> Option<usize> {
+        self.min_length
+    }
+
+    pub fn max_length(&self) -> Option<usize> {
+        self.max_length
+    }
+
+    pub fn allowed_chars(&self) -> Option<&str> {
+        self.allowed_chars.as_deref()
+    }
+
+    pub fn exists(&self) -> bool {
+        self.exists.unwrap_or(false)
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// Checks `value` against these constraints, returning the message to show when it fails: the
+    /// custom message if there is one, or one describing the first constraint broken.
+    pub fn validate(&self, value: &Value) -> Result<(), String> {
+        let result = match value {
+            Value::String(value) => self.validate_string(value),
+            Value::Number(value) => match value.as_i64() {
+                Some(value) => self.validate_int(value),
+                None => Ok(()),
+            },
+            Value::Array(items) => self.validate_count(items.len()).and_then(|_| {
+                items.iter().try_for_each(|item| match item {
+                    Value::String(item) => self.validate_string(item),
+                    _ => Ok(()),
+                })
+            }),
+            _ => Ok(()),
+        };
+        result.map_err(|message| self.message.clone().unwrap_or(message))
+    }
+
+    fn validate_int(&self, value: i64) -> Result<(), String> {
+        if let Some(min) = self.min {
+            if value < min {
+                return Err(format!("The value must be at least {}.", min));
+            }
+        }
+        if let Some(max) = self.max {
+            if value > max {
+                return Err(format!("The value must be at most {}.", max));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_count(&self, count: usize) -> Result<(), String> {
+        if let Some(min) = self.min {
+            if (count as i64) < min {
+                return Err(format!("At least {} item(s) are required.", min));
+            }
+        }
+        if let Some(max) = self.max {
+            if (count as i64) > max {
+                return Err(format!("At most {} item(s) are allowed.", max));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_string(&self, value: &str) -> Result<(), String> {
+        let length = value.chars().count();
+        if let Some(min_length) = self.min_length {
+            if length < min_length {
+                return Err(format!("The value must be at least {} characters long.", min_length));
+            }
+        }
+        if let Some(max_length) = self.max_length {
+            if length > max_length {
+                return Err(format!("The value must be at most {} characters long.", max_length));
+            }
+        }
+        if let Some(allowed_chars) = &self.allowed_chars {
+            if let Some(invalid) = value.chars().find(|c| !is_allowed(allowed_chars, *c)) {
+                return Err(format!(
+                    "The value may only contain the characters '{}', not '{}'.",
+                    allowed_chars, invalid
+                ));
+            }
+        }
+        if let Some(regex) = &self.regex {
+            if !regex.regex.is_match(value) {
+                return Err(format!("The value must match the pattern '{}'.", regex.source));
+            }
+        }
+        if self.exists() && !Path::new(value).exists() {
+            return Err(format!("The path '{}' does not exist.", value));
+        }
+        Ok(())
+    }
+}
+
+/// Whether `c` is one of `allowed_chars`, where `a-z` stands for a range. A `-` at either end is
+/// taken literally.
+fn is_allowed(allowed_chars: &str, c: char) -> bool {
+    let allowed = allowed_chars.chars().collect::<Vec<_>>();
+    let mut index = 0;
+    while index < allowed.len() {
+        if index + 2 < allowed.len() && allowed[index + 1] == '-' {
+            if allowed[index] <= c && c <= allowed[index + 2] {
+                return true;
+            }
+            index += 3;
+        } else {
+            if allowed[index] == c {
+                return true;
+            }
+            index += 1;
+        }
+    }
+    false
+}
+
+/// A regular expression, compiled as the archetype configuration is read so that invalid patterns
+/// are reported with the configuration, not the answers.
+#[derive(Clone, Debug)]
+struct RegexPattern {
+    source: String,
+    regex: Regex,
+}
+
+impl RegexPattern {
+    fn new(source: &str) -> Result<RegexPattern, regex::Error> {
+        Ok(RegexPattern {
+            source: source.to_owned(),
+            regex: Regex::new(source)?,
+        })
+    }
+}
+
+impl PartialEq for RegexPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for RegexPattern {}
+
+impl Serialize for RegexPattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for RegexPattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        RegexPattern::new(&source).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_string() {
+        let validation = Validation::new()
+            .with_regex("^[a-z]")
+            .unwrap()
+            .with_min_length(2)
+            .with_max_length(8)
+            .with_allowed_chars("a-z0-9_");
+
+        assert!(validation.validate(&json!("orders_2")).is_ok());
+        assert!(validation.validate(&json!("o")).is_err());
+        assert!(validation.validate(&json!("order_service")).is_err());
+        assert!(validation.validate(&json!("order-2")).is_err());
+        assert!(validation.validate(&json!("2orders")).is_err());
+        assert!(validation.validate(&json!(["orders", "Customers"])).is_err());
+    }
+
+    #[test]
+    fn test_validate_int() {
+        let validation = Validation::new().with_min(1).with_max(65535);
+        assert!(validation.validate(&json!(8080)).is_ok());
+        assert_eq!(
+            validation.validate(&json!(99999)),
+            Err("The value must be at most 65535.".to_owned())
+        );
+
+        let validation = validation.with_message("Please choose a valid port.");
+        assert_eq!(
+            validation.validate(&json!(0)),
+            Err("Please choose a valid port.".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_validate_count() {
+        let validation = Validation::new().with_min(1).with_max(2);
+        assert!(validation.validate(&json!(["postgres"])).is_ok());
+        assert!(validation.validate(&json!([])).is_err());
+        assert_eq!(
+            validation.validate(&json!(["postgres", "redis", "kafka"])),
+            Err("At most 2 item(s) are allowed.".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_validate_exists() {
+        let directory = tempfile::tempdir().unwrap();
+        let validation = Validation::new().with_exists(true);
+        assert!(validation.validate(&json!(directory.path())).is_ok());
+        assert!(validation.validate(&json!(directory.path().join("missing"))).is_err());
+    }
+
+    #[test]
+    fn test_allowed_chars() {
+        assert!(is_allowed("a-z-", 'q'));
+        assert!(is_allowed("a-z-", '-'));
+        assert!(is_allowed("-_.", '.'));
+        assert!(!is_allowed("a-z", 'A'));
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let validation: Validation = serde_yaml::from_str(
+            r#"
+            regex: "^[a-z][a-z0-9]*$"
+            max-length: 20
+            message: "Invalid package name."
+            "#,
+        )
+        .unwrap();
+        assert_eq!(validation.regex(), Some("^[a-z][a-z0-9]*$"));
+        assert_eq!(validation.max_length(), Some(20));
+        assert!(validation.validate(&json!("orders")).is_ok());
+
+        assert!(serde_yaml::from_str::<Validation>("regex: \"[a-z\"").is_err());
+    }
+}
@@ -1,8 +1,22 @@
+use linked_hash_map::LinkedHashMap;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::config::Validation;
+
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
 pub struct VariableInfo {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        deserialize_with = "deserialize_string_or_structure",
+        skip_serializing_if = "Option::is_none"
+    )]
     value: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        deserialize_with = "deserialize_string_or_structure",
+        skip_serializing_if = "Option::is_none"
+    )]
     default: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     prompt: Option<String>,
@@ -10,6 +24,18 @@ pub struct VariableInfo {
     required: Option<bool>,
     #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
     variable_type: Option<VariableType>,
+    #[serde(rename = "validate", skip_serializing_if = "Option::is_none")]
+    validation: Option<Validation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    when: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fallback: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    example: Option<String>,
 }
 
 impl VariableInfo {
@@ -21,6 +47,12 @@ impl VariableInfo {
                 prompt: None,
                 required: None,
                 variable_type: None,
+                validation: None,
+                when: None,
+                fallback: None,
+                description: None,
+                help: None,
+                example: None,
             },
         }
     }
@@ -33,6 +65,12 @@ impl VariableInfo {
                 prompt: None,
                 required: None,
                 variable_type: None,
+                validation: None,
+                when: None,
+                fallback: None,
+                description: None,
+                help: None,
+                example: None,
             },
         }
     }
@@ -45,6 +83,12 @@ impl VariableInfo {
                 prompt: None,
                 required: None,
                 variable_type: None,
+                validation: None,
+                when: None,
+                fallback: None,
+                description: None,
+                help: None,
+                example: None,
             },
         }
     }
@@ -57,6 +101,12 @@ impl VariableInfo {
                 default: None,
                 required: None,
                 variable_type: None,
+                validation: None,
+                when: None,
+                fallback: None,
+                description: None,
+                help: None,
+                example: None,
             },
         }
     }
@@ -90,6 +140,46 @@ impl VariableInfo {
         self.required.unwrap_or(true)
     }
 
+    /// A Tera expression deciding whether this variable is set at all.
+    pub fn when(&self) -> Option<&str> {
+        self.when.as_deref()
+    }
+
+    /// The value given to this variable when its `when` expression is false.
+    pub fn fallback(&self) -> Option<&str> {
+        self.fallback.as_deref()
+    }
+
+    /// A short description of what this variable is for, as listed in documentation and in reports of
+    /// missing answers.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// A longer explanation, shown with the description when `?` is answered at the prompt.
+    pub fn help(&self) -> Option<&str> {
+        self.help.as_deref()
+    }
+
+    /// An example answer, shown with the help.
+    pub fn example(&self) -> Option<&str> {
+        self.example.as_deref()
+    }
+
+    pub fn validation(&self) -> Option<&Validation> {
+        self.validation.as_ref()
+    }
+
+    /// Checks an answer against this variable's validation, if any. An empty answer to a variable that
+    /// is not required is always valid.
+    pub fn validate(&self, value: &Value) -> Result<(), String> {
+        match (&self.validation, value) {
+            (None, _) => Ok(()),
+            (Some(_), Value::String(value)) if value.is_empty() && !self.required() => Ok(()),
+            (Some(validation), value) => validation.validate(value),
+        }
+    }
+
     pub fn has_derived_value(&self) -> bool {
         self.prompt.is_none() && self.default.is_none() && self.value.is_some()
     }
@@ -101,12 +191,55 @@ pub enum VariableType {
     String,
     #[serde(rename = "int")]
     Int,
+    #[serde(rename = "float")]
+    Float,
     #[serde(rename = "bool")]
     Bool,
     #[serde(rename = "enum")]
     Enum(Vec<String>),
+    /// Any number of the options, exposed as a list.
+    #[serde(rename = "multi-enum", alias = "set")]
+    MultiEnum(Vec<String>),
     #[serde(rename = "array", alias = "list")]
     Array,
+    /// An object with the fields described by the nested variables.
+    #[serde(rename = "object")]
+    Object(LinkedHashMap<String, VariableInfo>),
+    /// A list of objects, each with the fields described by the nested variables.
+    #[serde(rename = "object-list")]
+    ObjectList(LinkedHashMap<String, VariableInfo>),
+    /// A file system path, with `~` and environment variables expanded.
+    #[serde(rename = "path")]
+    Path,
+    /// A semantic version, exposed as an object with its `version`, `major`, `minor`, `patch`, `pre`
+    /// and `build`.
+    #[serde(rename = "semver")]
+    Semver,
+    /// Text that may span several lines.
+    #[serde(rename = "text")]
+    Text,
+    /// A value that is not shown as it is entered, is never rendered as a template, and is left out of
+    /// the answers reported for a run.
+    #[serde(rename = "secret")]
+    Secret,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StringOrStructure {
+    String(String),
+    Structure(Value),
+}
+
+/// Reads a value given either as a string, or as a structure such as a list or a nested object for
+/// variables taking several values. Structures are kept in their JSON form, which both `parse_list`
+/// and JSON parsing read back.
+fn deserialize_string_or_structure<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    let value = Option::<StringOrStructure>::deserialize(deserializer)?;
+    Ok(value.map(|value| match value {
+        StringOrStructure::String(value) => value,
+        StringOrStructure::Structure(value) => value.to_string(),
+    }))
 }
 
 pub struct VariableInfoBuilder {
@@ -134,6 +267,36 @@ impl VariableInfoBuilder {
         self
     }
 
+    pub fn with_when<W: Into<String>>(mut self, when: W) -> VariableInfoBuilder {
+        self.variable_info.when = Some(when.into());
+        self
+    }
+
+    pub fn with_fallback<F: Into<String>>(mut self, fallback: F) -> VariableInfoBuilder {
+        self.variable_info.fallback = Some(fallback.into());
+        self
+    }
+
+    pub fn with_description<D: Into<String>>(mut self, description: D) -> VariableInfoBuilder {
+        self.variable_info.description = Some(description.into());
+        self
+    }
+
+    pub fn with_help<H: Into<String>>(mut self, help: H) -> VariableInfoBuilder {
+        self.variable_info.help = Some(help.into());
+        self
+    }
+
+    pub fn with_example<E: Into<String>>(mut self, example: E) -> VariableInfoBuilder {
+        self.variable_info.example = Some(example.into());
+        self
+    }
+
+    pub fn with_validation(mut self, validation: Validation) -> VariableInfoBuilder {
+        self.variable_info.validation = Some(validation);
+        self
+    }
+
     pub fn build(self) -> VariableInfo {
         self.variable_info
     }
@@ -1,31 +1,45 @@
 use std::collections::HashSet;
 use std::fs;
 use std::fs::File;
-use std::io::Write;
-use std::path::{Path, PathBuf};
-use std::rc::Rc;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 use clap::crate_version;
 use log::{debug, trace};
+use rayon::prelude::*;
 use semver::Version;
 
 use crate::config::RuleAction;
+use crate::events::{Event, EventListener};
+use crate::input::{FailingPrompter, MissingAnswer, Prompter, TerminalPrompter};
+use crate::rendering::Renderable;
+use crate::report::{CommandReport, PathOutcome, RenderReport};
 use crate::rules::RulesContext;
 use crate::system::layout::{dot_home_layout, LayoutType, NativeSystemLayout, SystemLayout};
 use crate::system::SystemError;
 use crate::template_engine::{Context, Tera};
-use crate::util::Source;
+use crate::util::{IgnoreFile, Source};
 use crate::{ArchetectError, Archetype, ArchetypeError, RenderError};
 
+const BINARY_DETECTION_CHUNK_SIZE: u64 = 8192;
+
 pub struct Archetect {
     tera: Tera,
-    paths: Rc<Box<dyn SystemLayout>>,
+    paths: Arc<Box<dyn SystemLayout>>,
     offline: bool,
     switches: HashSet<String>,
+    reports: Mutex<Vec<RenderReport>>,
+    prompter: Box<dyn Prompter>,
+    events: Option<Box<dyn EventListener>>,
+    headless: bool,
+    confirm: bool,
+    reserved_stdout: bool,
+    missing_answers: Mutex<Vec<MissingAnswer>>,
 }
 
 impl Archetect {
-    pub fn layout(&self) -> Rc<Box<dyn SystemLayout>> {
+    pub fn layout(&self) -> Arc<Box<dyn SystemLayout>> {
         self.paths.clone()
     }
 
@@ -45,6 +59,48 @@ impl Archetect {
         &self.tera
     }
 
+    pub fn prompter(&self) -> &dyn Prompter {
+        self.prompter.as_ref()
+    }
+
+    /// Whether this Archetect runs without prompting, collecting the answers it is missing instead of
+    /// failing on the first.
+    pub fn headless(&self) -> bool {
+        self.headless
+    }
+
+    /// Whether the answers to an archetype's prompts are reviewed before it goes on to render anything.
+    /// Archetypes may also ask for this themselves.
+    pub fn confirm(&self) -> bool {
+        self.confirm
+    }
+
+    /// Sends `event` to the event listener this Archetect was built with, if any.
+    pub fn emit(&self, event: Event) {
+        if let Some(events) = &self.events {
+            events.on_event(&event);
+        }
+    }
+
+    /// Whether stdout is kept for machine-readable output, such as events, so nothing else may be
+    /// written to it.
+    pub(crate) fn reserves_stdout(&self) -> bool {
+        self.reserved_stdout || self.events.is_some()
+    }
+
+    /// Writes the text of a `print` action: as a `print` event while events are listened for, to stderr
+    /// while stdout is otherwise reserved, and to stdout otherwise.
+    pub(crate) fn print<M: Into<String>>(&self, message: M) {
+        let message = message.into();
+        if self.events.is_some() {
+            self.emit(Event::Print { message });
+        } else if self.reserved_stdout {
+            eprintln!("{}", message);
+        } else {
+            println!("{}", message);
+        }
+    }
+
     pub fn enable_switch<S: Into<String>>(&mut self, switch: S) {
         self.switches.insert(switch.into());
     }
@@ -53,6 +109,67 @@ impl Archetect {
         &self.switches
     }
 
+    /// Starts recording a report for an archetype run. Runs nest, as archetypes may render others, and
+    /// scopes report their answers separately from the run they are part of.
+    pub(crate) fn begin_report(&self) {
+        self.reports.lock().unwrap().push(RenderReport::new());
+    }
+
+    /// Finishes the innermost report, folding it into the report of the enclosing run, if any.
+    pub(crate) fn end_report(&self) -> RenderReport {
+        let mut reports = self.reports.lock().unwrap();
+        let report = reports.pop().unwrap_or_default();
+        if let Some(parent) = reports.last_mut() {
+            parent.absorb(report.clone());
+        }
+        report
+    }
+
+    pub(crate) fn record_path<P: Into<PathBuf>>(&self, path: P, outcome: PathOutcome) {
+        let path = path.into();
+        self.emit(Event::Path {
+            path: path.clone(),
+            outcome,
+        });
+        if let Some(report) = self.reports.lock().unwrap().last_mut() {
+            report.add_path(path, outcome);
+        }
+    }
+
+    pub(crate) fn record_command(&self, command: CommandReport) {
+        self.emit(Event::CommandFinished {
+            command: command.command().to_owned(),
+            exit_code: command.exit_code(),
+            error: command.error().map(|error| error.to_owned()),
+        });
+        if let Some(report) = self.reports.lock().unwrap().last_mut() {
+            report.add_command(command);
+        }
+    }
+
+    /// Whether a report is being recorded, that is, whether an archetype run is in progress.
+    pub(crate) fn in_run(&self) -> bool {
+        !self.reports.lock().unwrap().is_empty()
+    }
+
+    pub(crate) fn record_missing_answer(&self, missing_answer: MissingAnswer) {
+        self.missing_answers.lock().unwrap().push(missing_answer);
+    }
+
+    pub(crate) fn has_missing_answers(&self) -> bool {
+        !self.missing_answers.lock().unwrap().is_empty()
+    }
+
+    pub(crate) fn take_missing_answers(&self) -> Vec<MissingAnswer> {
+        std::mem::take(&mut *self.missing_answers.lock().unwrap())
+    }
+
+    pub(crate) fn record_answer<K: Into<String>>(&self, identifier: K, value: serde_json::Value) {
+        if let Some(report) = self.reports.lock().unwrap().last_mut() {
+            report.add_answer(identifier, value);
+        }
+    }
+
     pub fn load_archetype(&self, source: &str, relative_to: Option<Source>) -> Result<Archetype, ArchetypeError> {
         let source = Source::detect(self, source, relative_to)?;
         let archetype = Archetype::from_source(&source)?;
@@ -60,7 +177,17 @@ impl Archetect {
     }
 
     pub fn render_string(&self, template: &str, context: &Context) -> Result<String, RenderError> {
-        match self.tera.render_string(template, context.clone()) {
+        self.render_string_with(&self.tera, template, context)
+    }
+
+    /// Renders `template` with `template_engine`, typically an archetype's own.
+    pub fn render_string_with(
+        &self,
+        template_engine: &Tera,
+        template: &str,
+        context: &Context,
+    ) -> Result<String, RenderError> {
+        match template_engine.render_string(template, context.clone()) {
             Ok(result) => Ok(result),
             Err(err) => {
                 // TODO: Get a better error message.
@@ -74,7 +201,24 @@ impl Archetect {
         }
     }
 
+    /// Evaluates a Tera expression, such as `database and port > 1024`, for its truthiness.
+    pub fn evaluate_condition(&self, expression: &str, context: &Context) -> Result<bool, RenderError> {
+        let template = format!("{{% if {} %}}true{{% endif %}}", expression);
+        Ok(self.render_string(&template, context)? == "true")
+    }
+
     pub fn render_contents<P: AsRef<Path>>(&self, path: P, context: &Context) -> Result<String, RenderError> {
+        self.render_contents_with(&self.tera, path, context)
+    }
+
+    /// Renders the file at `path` with `template_engine`, typically an archetype's own, so the file can
+    /// `include`, `import` from and `extend` the templates loaded into it.
+    pub fn render_contents_with<P: AsRef<Path>>(
+        &self,
+        template_engine: &Tera,
+        path: P,
+        context: &Context,
+    ) -> Result<String, RenderError> {
         let path = path.as_ref();
         let template = match fs::read_to_string(path) {
             Ok(template) => template,
@@ -86,7 +230,7 @@ impl Archetect {
                 });
             }
         };
-        match self.tera.render_string(&template, context.clone()) {
+        match template_engine.render_string(&template, context.clone()) {
             Ok(result) => Ok(result),
             Err(error) => {
                 // TODO: Get a better error message.
@@ -100,68 +244,346 @@ impl Archetect {
         }
     }
 
+    /// Renders the template file at `source` to `destination`, following the same destination rules as
+    /// directory rendering.
+    pub fn render_file_with<SRC: AsRef<Path>, DEST: AsRef<Path>>(
+        &self,
+        template_engine: &Tera,
+        context: &Context,
+        source: SRC,
+        destination: DEST,
+        rules_context: &RulesContext,
+    ) -> Result<(), RenderError> {
+        let source = source.as_ref();
+        let destination = destination.as_ref();
+        create_parent_directories(destination)?;
+        let outcome = self.write_rendered(destination, rules_context, || {
+            self.render_contents_with(template_engine, source, context)
+        })?;
+        if outcome.is_written() {
+            copy_permissions(source, destination)?;
+        }
+        outcome.log(destination);
+        self.record_path(destination, outcome.path_outcome());
+        Ok(())
+    }
+
+    /// Renders the inline `template` to `destination`, following the same destination rules as
+    /// directory rendering.
+    pub fn render_template_with<DEST: AsRef<Path>>(
+        &self,
+        template_engine: &Tera,
+        context: &Context,
+        template: &str,
+        destination: DEST,
+        rules_context: &RulesContext,
+    ) -> Result<(), RenderError> {
+        let destination = destination.as_ref();
+        create_parent_directories(destination)?;
+        let outcome = self.write_rendered(destination, rules_context, || {
+            self.render_string_with(template_engine, template, context)
+        })?;
+        outcome.log(destination);
+        self.record_path(destination, outcome.path_outcome());
+        Ok(())
+    }
+
     pub fn render_directory<SRC: Into<PathBuf>, DEST: Into<PathBuf>>(
         &self,
         context: &Context,
         source: SRC,
         destination: DEST,
         rules_context: &mut RulesContext,
+    ) -> Result<(), RenderError> {
+        self.render_directory_with(&self.tera, context, source, destination, rules_context)
+    }
+
+    /// Renders the `source` directory tree into `destination`, rendering file contents with
+    /// `template_engine`. The tree is walked first, in file name order, and the files are then rendered
+    /// and written in parallel. Outcomes are logged in walk order, and every error encountered is
+    /// reported together.
+    pub fn render_directory_with<SRC: Into<PathBuf>, DEST: Into<PathBuf>>(
+        &self,
+        template_engine: &Tera,
+        context: &Context,
+        source: SRC,
+        destination: DEST,
+        rules_context: &mut RulesContext,
+    ) -> Result<(), RenderError> {
+        let source = source.into();
+        self.render_directory_within(
+            template_engine,
+            &source.clone(),
+            context,
+            source,
+            destination,
+            rules_context,
+        )
+    }
+
+    /// Renders the `source` directory tree like `render_directory_with`, allowing its symlinks to point
+    /// anywhere within `root`, such as the directory of the archetype `source` belongs to.
+    pub fn render_directory_within<SRC: Into<PathBuf>, DEST: Into<PathBuf>>(
+        &self,
+        template_engine: &Tera,
+        root: &Path,
+        context: &Context,
+        source: SRC,
+        destination: DEST,
+        rules_context: &mut RulesContext,
     ) -> Result<(), RenderError> {
         let source = source.into();
         let destination = destination.into();
 
-        for entry in fs::read_dir(&source)? {
-            let entry = entry?;
+        let mut tasks = vec![];
+        let walk = TreeWalk {
+            context,
+            root,
+            rules_context,
+        };
+        let ignores = IgnoreFile::load(&source)?;
+        self.collect_tasks(&walk, &ignores, &source, &destination, &mut tasks)?;
+
+        for task in &tasks {
+            if let RenderTask::Directory { destination } = task {
+                fs::create_dir_all(destination)?;
+            }
+        }
+
+        let rules_context: &RulesContext = rules_context;
+        let results: Vec<Result<RenderOutcome, RenderError>> = tasks
+            .par_iter()
+            .map(|task| self.render_task(template_engine, task, context, rules_context))
+            .collect();
+
+        let mut errors = vec![];
+        for (task, result) in tasks.iter().zip(results) {
+            match result {
+                Ok(outcome) => {
+                    outcome.log(task.destination());
+                    self.record_path(task.destination(), outcome.path_outcome());
+                }
+                Err(error) => errors.push(error),
+            }
+        }
+
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(RenderError::RenderErrors(errors)),
+        }
+    }
+
+    fn collect_tasks(
+        &self,
+        walk: &TreeWalk,
+        ignores: &IgnoreFile,
+        source: &Path,
+        destination: &Path,
+        tasks: &mut Vec<RenderTask>,
+    ) -> Result<(), RenderError> {
+        let mut entries = fs::read_dir(source)?.collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
             let path = entry.path();
+            let file_type = entry.file_type()?;
+
+            if ignores.is_ignored(&path, file_type.is_dir()) {
+                trace!("Ignoring    {:?}", path);
+                continue;
+            }
+
+            let action = walk.rules_context.find_source_action(path.as_path());
+
+            // In suffix mode, template files lose their suffix and everything else is copied, unless a
+            // source rule says otherwise. Template files are rendered as any other file would be, so
+            // binary ones are still copied unless a rule says to render them.
+            let (name, action) = match walk.rules_context.template_suffixes() {
+                Some(suffixes) if file_type.is_file() => match strip_template_suffix(&path, suffixes) {
+                    Some(name) => (path.with_file_name(name), action),
+                    None => (path.clone(), action.or(Some(RuleAction::COPY))),
+                },
+                _ => (path.clone(), action),
+            };
 
-            let action = rules_context.get_source_action(path.as_path());
+            let destination = match self.render_destination(destination, &name, walk.context)? {
+                Some(destination) => destination,
+                None => {
+                    trace!("Skipping    {:?} (name rendered empty)", path);
+                    continue;
+                }
+            };
 
-            if path.is_dir() {
-                let destination = self.render_destination(&destination, &path, &context)?;
-                debug!("Rendering   {:?}", &destination);
-                fs::create_dir_all(destination.as_path())?;
-                self.render_directory(context, path, destination, rules_context)?;
-            } else if path.is_file() {
-                let destination = self.render_destination(&destination, &path, &context)?;
-                match action {
+            if file_type.is_symlink() {
+                let link = fs::read_link(&path)?;
+                if escapes_root(walk.root, source, &link) {
+                    return Err(RenderError::InvalidSymlink {
+                        source: path,
+                        target: link,
+                    });
+                }
+                tasks.push(RenderTask::Symlink {
+                    root: walk.root.to_owned(),
+                    source: path,
+                    link,
+                    destination,
+                    action: action.unwrap_or_default(),
+                });
+            } else if file_type.is_dir() {
+                tasks.push(RenderTask::Directory {
+                    destination: destination.clone(),
+                });
+                // A directory's own ignore file applies to everything beneath it, over its parents' rules.
+                let nested = ignores.nested(&path)?;
+                self.collect_tasks(walk, nested.as_ref().unwrap_or(ignores), &path, &destination, tasks)?;
+            } else if file_type.is_file() {
+                tasks.push(RenderTask::File {
+                    source: path,
+                    destination,
+                    action,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render_task(
+        &self,
+        template_engine: &Tera,
+        task: &RenderTask,
+        context: &Context,
+        rules_context: &RulesContext,
+    ) -> Result<RenderOutcome, RenderError> {
+        match task {
+            RenderTask::Directory { destination: _ } => Ok(RenderOutcome::Created),
+            RenderTask::File {
+                source,
+                destination,
+                action,
+            } => {
+                // Files that are not explicitly matched by a rule are copied verbatim if they look binary;
+                // a RENDER rule renders them regardless.
+                if action.is_none() && is_binary(source)? {
+                    self.copy_contents(source, destination)?;
+                    return Ok(RenderOutcome::CopiedBinary);
+                }
+                match action.clone().unwrap_or_default() {
                     RuleAction::RENDER => {
-                        if !destination.exists() {
-                            debug!("Rendering   {:?}", destination);
-                            let contents = self.render_contents(&path, &context)?;
-                            self.write_contents(destination, &contents)?;
-                        } else if rules_context.overwrite() {
-                            debug!("Overwriting {:?}", destination);
-                            let contents = self.render_contents(&path, &context)?;
-                            self.write_contents(destination, &contents)?;
-                        } else {
-                            trace!("Preserving  {:?}", destination);
+                        let outcome = self.write_rendered(destination, rules_context, || {
+                            self.render_contents_with(template_engine, source, context)
+                        })?;
+                        if outcome.is_written() {
+                            copy_permissions(source, destination)?;
                         }
+                        Ok(outcome)
                     }
                     RuleAction::COPY => {
-                        debug!("Copying     {:?}", destination);
-                        self.copy_contents(&path, &destination)?;
-                    }
-                    RuleAction::SKIP => {
-                        trace!("Skipping    {:?}", destination);
+                        self.copy_contents(source, destination)?;
+                        Ok(RenderOutcome::Copied)
                     }
+                    RuleAction::SKIP => Ok(RenderOutcome::Skipped),
+                }
+            }
+            RenderTask::Symlink {
+                root,
+                source,
+                link,
+                destination,
+                action,
+            } => {
+                let target = match action {
+                    RuleAction::SKIP => return Ok(RenderOutcome::Skipped),
+                    RuleAction::COPY => link.to_owned(),
+                    RuleAction::RENDER => link.as_path().render(self, context)?,
+                };
+                // The link was checked before it was rendered; its rendered target must still stay within.
+                if escapes_root(root, source.parent().unwrap_or(root), &target) {
+                    return Err(RenderError::InvalidSymlink {
+                        source: source.to_owned(),
+                        target,
+                    });
                 }
+                self.render_symlink(source, link, &target, destination, rules_context)
             }
         }
+    }
 
-        Ok(())
+    /// Writes the contents produced by `render` to `destination`, unless the destination rules preserve
+    /// an existing file or skip empty ones. Contents are only rendered if they could be written.
+    fn write_rendered<F>(
+        &self,
+        destination: &Path,
+        rules_context: &RulesContext,
+        render: F,
+    ) -> Result<RenderOutcome, RenderError>
+    where
+        F: FnOnce() -> Result<String, RenderError>,
+    {
+        let outcome = if !destination.exists() {
+            RenderOutcome::Rendered
+        } else if rules_context.overwrite() {
+            RenderOutcome::Overwritten
+        } else {
+            return Ok(RenderOutcome::Preserved);
+        };
+        let contents = render()?;
+        if rules_context.skip_empty_files() && contents.trim().is_empty() {
+            return Ok(RenderOutcome::SkippedEmpty);
+        }
+        self.write_contents(destination, &contents)?;
+        Ok(outcome)
     }
 
+    /// Recreates the symlink at `source`, pointing at `link`, as a symlink at `destination` pointing at
+    /// `target`, the link as rendered.
+    fn render_symlink(
+        &self,
+        source: &Path,
+        link: &Path,
+        target: &Path,
+        destination: &Path,
+        rules_context: &RulesContext,
+    ) -> Result<RenderOutcome, RenderError> {
+        let outcome = if let Ok(metadata) = fs::symlink_metadata(destination) {
+            if !rules_context.overwrite() {
+                return Ok(RenderOutcome::Preserved);
+            }
+            if metadata.is_dir() {
+                fs::remove_dir_all(destination)?;
+            } else {
+                fs::remove_file(destination)?;
+            }
+            RenderOutcome::Overwritten
+        } else {
+            RenderOutcome::Linked
+        };
+
+        let is_dir = source
+            .parent()
+            .map(|parent| parent.join(link).is_dir())
+            .unwrap_or(false);
+        create_symlink(target, destination, is_dir)?;
+        Ok(outcome)
+    }
+
+    /// Renders the file name of `child` into `parent`. Returns `None` if the name renders empty or to
+    /// whitespace, meaning the path and everything beneath it is not generated.
     fn render_destination<P: AsRef<Path>, C: AsRef<Path>>(
         &self,
         parent: P,
         child: C,
         context: &Context,
-    ) -> Result<PathBuf, RenderError> {
+    ) -> Result<Option<PathBuf>, RenderError> {
         let mut destination = parent.as_ref().to_owned();
         let child = child.as_ref();
         let name = self.render_path(&child, &context)?;
+        if name.trim().is_empty() {
+            return Ok(None);
+        }
         destination.push(name);
-        Ok(destination)
+        Ok(Some(destination))
     }
 
     fn render_path<P: AsRef<Path>>(&self, path: P, context: &Context) -> Result<String, RenderError> {
@@ -200,10 +622,171 @@ impl Archetect {
     }
 }
 
+/// The state shared by every level of a directory walk.
+struct TreeWalk<'a> {
+    context: &'a Context,
+    root: &'a Path,
+    rules_context: &'a RulesContext,
+}
+
+enum RenderTask {
+    Directory {
+        destination: PathBuf,
+    },
+    File {
+        source: PathBuf,
+        destination: PathBuf,
+        action: Option<RuleAction>,
+    },
+    Symlink {
+        root: PathBuf,
+        source: PathBuf,
+        link: PathBuf,
+        destination: PathBuf,
+        action: RuleAction,
+    },
+}
+
+impl RenderTask {
+    fn destination(&self) -> &Path {
+        match self {
+            RenderTask::Directory { destination } => destination,
+            RenderTask::File { destination, .. } => destination,
+            RenderTask::Symlink { destination, .. } => destination,
+        }
+    }
+}
+
+enum RenderOutcome {
+    Created,
+    Rendered,
+    Overwritten,
+    Preserved,
+    Copied,
+    CopiedBinary,
+    Linked,
+    Skipped,
+    SkippedEmpty,
+}
+
+impl RenderOutcome {
+    fn path_outcome(&self) -> PathOutcome {
+        match self {
+            RenderOutcome::Created => PathOutcome::Created,
+            RenderOutcome::Rendered => PathOutcome::Rendered,
+            RenderOutcome::Overwritten => PathOutcome::Overwritten,
+            RenderOutcome::Preserved => PathOutcome::Preserved,
+            RenderOutcome::Copied | RenderOutcome::CopiedBinary => PathOutcome::Copied,
+            RenderOutcome::Linked => PathOutcome::Linked,
+            RenderOutcome::Skipped | RenderOutcome::SkippedEmpty => PathOutcome::Skipped,
+        }
+    }
+
+    fn is_written(&self) -> bool {
+        matches!(self, RenderOutcome::Rendered | RenderOutcome::Overwritten)
+    }
+
+    fn log(&self, destination: &Path) {
+        match self {
+            RenderOutcome::Created => debug!("Rendering   {:?}", destination),
+            RenderOutcome::Rendered => debug!("Rendering   {:?}", destination),
+            RenderOutcome::Overwritten => debug!("Overwriting {:?}", destination),
+            RenderOutcome::Preserved => trace!("Preserving  {:?}", destination),
+            RenderOutcome::Copied => debug!("Copying     {:?}", destination),
+            RenderOutcome::CopiedBinary => debug!("Copying     {:?} (binary content detected)", destination),
+            RenderOutcome::Linked => debug!("Linking     {:?}", destination),
+            RenderOutcome::Skipped => trace!("Skipping    {:?}", destination),
+            RenderOutcome::SkippedEmpty => trace!("Skipping    {:?} (contents rendered empty)", destination),
+        }
+    }
+}
+
+fn create_parent_directories(destination: &Path) -> Result<(), RenderError> {
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    Ok(())
+}
+
+/// Applies the permissions of `source`, such as the executable bit, to `destination`.
+fn copy_permissions(source: &Path, destination: &Path) -> Result<(), RenderError> {
+    let permissions = fs::metadata(source)?.permissions();
+    fs::set_permissions(destination, permissions)?;
+    Ok(())
+}
+
+/// Detects binary content by looking for NUL bytes or invalid UTF-8 in the first chunk of a file.
+fn is_binary(path: &Path) -> Result<bool, RenderError> {
+    let mut chunk = Vec::with_capacity(BINARY_DETECTION_CHUNK_SIZE as usize);
+    File::open(path)?
+        .take(BINARY_DETECTION_CHUNK_SIZE)
+        .read_to_end(&mut chunk)?;
+    if chunk.contains(&0) {
+        return Ok(true);
+    }
+    match std::str::from_utf8(&chunk) {
+        Ok(_) => Ok(false),
+        // A multi-byte character cut off at the end of the chunk is not invalid UTF-8.
+        Err(error) => Ok(error.error_len().is_some()),
+    }
+}
+
+/// Returns the file name of `path` without the first of `suffixes` it ends in, or `None` if it ends in
+/// none of them.
+fn strip_template_suffix<'a>(path: &'a Path, suffixes: &[String]) -> Option<&'a str> {
+    let name = path.file_name()?.to_str()?;
+    suffixes
+        .iter()
+        .filter_map(|suffix| name.strip_suffix(suffix.as_str()))
+        .find(|stem| !stem.is_empty())
+}
+
+/// Determines, without touching the file system, whether a symlink in `link_parent` pointing at
+/// `target` would resolve to a location outside of `root`.
+fn escapes_root(root: &Path, link_parent: &Path, target: &Path) -> bool {
+    let mut depth = match link_parent.strip_prefix(root) {
+        Ok(relative) => relative.components().count(),
+        Err(_) => return true,
+    };
+    for component in target.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => (),
+            Component::ParentDir => {
+                if depth == 0 {
+                    return true;
+                }
+                depth -= 1;
+            }
+            Component::RootDir | Component::Prefix(_) => return true,
+        }
+    }
+    false
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path, _is_dir: bool) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &Path, link: &Path, is_dir: bool) -> std::io::Result<()> {
+    if is_dir {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    }
+}
+
 pub struct ArchetectBuilder {
     layout: Option<Box<dyn SystemLayout>>,
     offline: bool,
     switches: HashSet<String>,
+    prompter: Option<Box<dyn Prompter>>,
+    events: Option<Box<dyn EventListener>>,
+    headless: bool,
+    confirm: bool,
+    reserved_stdout: bool,
 }
 
 impl ArchetectBuilder {
@@ -212,18 +795,38 @@ impl ArchetectBuilder {
             layout: None,
             offline: false,
             switches: HashSet::new(),
+            prompter: None,
+            events: None,
+            headless: false,
+            confirm: false,
+            reserved_stdout: false,
         }
     }
 
     pub fn build(self) -> Result<Archetect, ArchetectError> {
         let layout = dot_home_layout()?;
         let paths = self.layout.unwrap_or_else(|| Box::new(layout));
-        let paths = Rc::new(paths);
+        let paths = Arc::new(paths);
+        let headless = self.headless;
+        let prompter = self.prompter.unwrap_or_else(|| {
+            if headless {
+                Box::new(FailingPrompter)
+            } else {
+                Box::new(TerminalPrompter)
+            }
+        });
         Ok(Archetect {
             tera: Tera::default(),
             paths,
             offline: self.offline,
             switches: self.switches,
+            reports: Mutex::new(vec![]),
+            prompter,
+            events: self.events,
+            headless,
+            confirm: self.confirm,
+            reserved_stdout: self.reserved_stdout,
+            missing_answers: Mutex::new(vec![]),
         })
     }
 
@@ -245,10 +848,46 @@ impl ArchetectBuilder {
         self.offline = offline;
         self
     }
+
+    /// Runs without prompting: defaults are used where they exist, and every variable left without an
+    /// answer is reported together once the run finishes. Unless a prompter is set, prompts fail.
+    pub fn with_headless(mut self, headless: bool) -> ArchetectBuilder {
+        self.headless = headless;
+        self
+    }
+
+    /// Reviews the answers to the top-level prompts of the archetype being rendered once they are all
+    /// given, so they may be accepted, changed or abandoned before anything is written.
+    pub fn with_confirm(mut self, confirm: bool) -> ArchetectBuilder {
+        self.confirm = confirm;
+        self
+    }
+
+    /// Keeps stdout for machine-readable output, such as a report, sending the text of `print` actions
+    /// and the output of commands to stderr instead.
+    pub fn with_reserved_stdout(mut self, reserved_stdout: bool) -> ArchetectBuilder {
+        self.reserved_stdout = reserved_stdout;
+        self
+    }
+
+    /// Sets the prompter used to ask for answers. Defaults to prompting on the terminal, or to failing
+    /// when headless.
+    pub fn with_prompter<P: Prompter + 'static>(mut self, prompter: P) -> ArchetectBuilder {
+        self.prompter = Some(Box::new(prompter));
+        self
+    }
+
+    pub fn with_event_listener<L: EventListener + 'static>(mut self, listener: L) -> ArchetectBuilder {
+        self.events = Some(Box::new(listener));
+        self
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use linked_hash_map::LinkedHashMap;
+
+    use crate::config::{Pattern, RuleConfig};
     use crate::system::layout::{NativeSystemLayout, RootedSystemLayout};
 
     use super::*;
@@ -280,4 +919,486 @@ mod tests {
         std::fs::create_dir_all(archetect.layout().configs_dir()).expect("Error creating directory");
         std::fs::create_dir_all(archetect.layout().git_cache_dir()).expect("Error creating directory");
     }
+
+    #[test]
+    fn test_escapes_root() {
+        let root = Path::new("/archetype/contents");
+        assert!(!escapes_root(root, root, Path::new("gradlew")));
+        assert!(!escapes_root(root, &root.join("bin"), Path::new("../scripts/run.sh")));
+        assert!(!escapes_root(root, &root.join("bin"), Path::new("./{{ name }}.sh")));
+        assert!(escapes_root(root, root, Path::new("../archetype.yml")));
+        assert!(escapes_root(root, &root.join("bin"), Path::new("../../secrets")));
+        assert!(escapes_root(root, root, Path::new("/etc/passwd")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_render_directory_permissions_and_symlinks() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let source = tempfile::tempdir().unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let archetect = Archetect::build().unwrap();
+        let mut context = Context::new();
+        context.insert("name", "example");
+
+        fs::write(source.path().join("gradlew"), "#!/bin/sh\necho {{ name }}\n").unwrap();
+        fs::set_permissions(source.path().join("gradlew"), fs::Permissions::from_mode(0o755)).unwrap();
+        fs::write(source.path().join("{{ name }}.txt"), "{{ name }}").unwrap();
+        symlink("{{ name }}.txt", source.path().join("latest.txt")).unwrap();
+
+        archetect
+            .render_directory(&context, source.path(), destination.path(), &mut RulesContext::new())
+            .unwrap();
+
+        let mode = fs::metadata(destination.path().join("gradlew"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o755);
+        assert_eq!(
+            fs::read_link(destination.path().join("latest.txt")).unwrap(),
+            PathBuf::from("example.txt")
+        );
+        assert_eq!(
+            fs::read_to_string(destination.path().join("latest.txt")).unwrap(),
+            "example"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_render_directory_refuses_external_symlinks() {
+        let source = tempfile::tempdir().unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let archetect = Archetect::build().unwrap();
+
+        std::os::unix::fs::symlink("../../etc/passwd", source.path().join("passwd")).unwrap();
+
+        match archetect.render_directory(
+            &Context::new(),
+            source.path(),
+            destination.path(),
+            &mut RulesContext::new(),
+        ) {
+            Err(RenderError::InvalidSymlink { source: _, target }) => {
+                assert_eq!(target, PathBuf::from("../../etc/passwd"))
+            }
+            _ => panic!("InvalidSymlink expected"),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_render_directory_refuses_rendered_external_symlinks() {
+        let source = tempfile::tempdir().unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let archetect = Archetect::build().unwrap();
+        let mut context = Context::new();
+        context.insert("target", "../../../../etc/passwd");
+
+        std::os::unix::fs::symlink("{{ target }}", source.path().join("passwd")).unwrap();
+
+        match archetect.render_directory(&context, source.path(), destination.path(), &mut RulesContext::new()) {
+            Err(RenderError::InvalidSymlink { source: _, target }) => {
+                assert_eq!(target, PathBuf::from("../../../../etc/passwd"))
+            }
+            _ => panic!("InvalidSymlink expected"),
+        }
+        assert!(fs::symlink_metadata(destination.path().join("passwd")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_render_directory_within_archetype_root() {
+        let root = tempfile::tempdir().unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let archetect = Archetect::build().unwrap();
+        let contents = root.path().join("contents");
+        fs::create_dir_all(&contents).unwrap();
+        fs::create_dir_all(root.path().join("shared")).unwrap();
+        std::os::unix::fs::symlink("../shared/LICENSE", contents.join("LICENSE")).unwrap();
+        std::os::unix::fs::symlink("../../LICENSE", contents.join("outside")).unwrap();
+
+        let result = archetect.render_directory_within(
+            archetect.template_engine(),
+            root.path(),
+            &Context::new(),
+            &contents,
+            destination.path(),
+            &mut RulesContext::new(),
+        );
+        match result {
+            Err(RenderError::InvalidSymlink { source, target: _ }) => assert_eq!(source, contents.join("outside")),
+            _ => panic!("InvalidSymlink expected"),
+        }
+
+        fs::remove_file(contents.join("outside")).unwrap();
+        archetect
+            .render_directory_within(
+                archetect.template_engine(),
+                root.path(),
+                &Context::new(),
+                &contents,
+                destination.path(),
+                &mut RulesContext::new(),
+            )
+            .unwrap();
+        assert_eq!(
+            fs::read_link(destination.path().join("LICENSE")).unwrap(),
+            PathBuf::from("../shared/LICENSE")
+        );
+    }
+
+    #[test]
+    fn test_is_binary() {
+        let directory = tempfile::tempdir().unwrap();
+        let text = directory.path().join("README.md");
+        let image = directory.path().join("logo.png");
+        let latin1 = directory.path().join("latin1.txt");
+        fs::write(&text, "# {{ name }} ✓").unwrap();
+        fs::write(&image, [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00]).unwrap();
+        fs::write(&latin1, [b'c', b'a', b'f', 0xe9, b'!']).unwrap();
+
+        assert!(!is_binary(&text).unwrap());
+        assert!(is_binary(&image).unwrap());
+        assert!(is_binary(&latin1).unwrap());
+    }
+
+    #[test]
+    fn test_render_directory_copies_binary_files() {
+        let source = tempfile::tempdir().unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let archetect = Archetect::build().unwrap();
+        let contents = [0x00, b'{', b'{', 0xff, b'}', b'}'];
+        fs::write(source.path().join("font.ttf"), contents).unwrap();
+
+        archetect
+            .render_directory(
+                &Context::new(),
+                source.path(),
+                destination.path(),
+                &mut RulesContext::new(),
+            )
+            .unwrap();
+
+        assert_eq!(fs::read(destination.path().join("font.ttf")).unwrap(), contents);
+    }
+
+    #[test]
+    fn test_render_directory_renders_binary_files_by_rule() {
+        let source = tempfile::tempdir().unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let archetect = Archetect::build().unwrap();
+        let mut context = Context::new();
+        context.insert("name", "example");
+        fs::write(source.path().join("records.dat"), "\0{{ name }}").unwrap();
+        fs::write(source.path().join("index.dat"), "\0{{ name }}").unwrap();
+
+        let mut rules = LinkedHashMap::new();
+        rules.insert(
+            "records".to_owned(),
+            RuleConfig::new()
+                .with_pattern(Pattern::GLOB("**/records.dat".to_owned()))
+                .with_action(RuleAction::RENDER),
+        );
+        let mut rules_context = RulesContext::new();
+        rules_context.insert_path_rules(&rules);
+        archetect
+            .render_directory(&context, source.path(), destination.path(), &mut rules_context)
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(destination.path().join("records.dat")).unwrap(),
+            "\0example"
+        );
+        assert_eq!(
+            fs::read_to_string(destination.path().join("index.dat")).unwrap(),
+            "\0{{ name }}"
+        );
+    }
+
+    #[test]
+    fn test_render_directory_with_template_suffixes_copies_binary_files() {
+        let source = tempfile::tempdir().unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let archetect = Archetect::build().unwrap();
+        let mut context = Context::new();
+        context.insert("name", "example");
+        fs::write(source.path().join("records.dat.tera"), "\0{{ name }}").unwrap();
+        fs::write(source.path().join("index.dat.tera"), "\0{{ name }}").unwrap();
+
+        let mut rules = LinkedHashMap::new();
+        rules.insert(
+            "records".to_owned(),
+            RuleConfig::new()
+                .with_pattern(Pattern::GLOB("**/records.dat.tera".to_owned()))
+                .with_action(RuleAction::RENDER),
+        );
+        let mut rules_context = RulesContext::new();
+        rules_context.insert_path_rules(&rules);
+        rules_context.set_template_suffixes(Some(vec![".tera".to_owned()]));
+        archetect
+            .render_directory(&context, source.path(), destination.path(), &mut rules_context)
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(destination.path().join("records.dat")).unwrap(),
+            "\0example"
+        );
+        assert_eq!(
+            fs::read_to_string(destination.path().join("index.dat")).unwrap(),
+            "\0{{ name }}"
+        );
+    }
+
+    #[test]
+    fn test_render_directory_honors_ignore_file() {
+        let source = tempfile::tempdir().unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let archetect = Archetect::build().unwrap();
+        fs::create_dir_all(source.path().join(".git").join("objects")).unwrap();
+        fs::write(source.path().join(".git").join("HEAD"), "ref: refs/heads/main").unwrap();
+        fs::create_dir(source.path().join("build")).unwrap();
+        fs::write(source.path().join("build").join("{{ missing }}.txt"), "{{ missing }}").unwrap();
+        fs::write(source.path().join(".DS_Store"), [0x00]).unwrap();
+        fs::write(source.path().join(".archetectignore"), "build/\n").unwrap();
+        fs::write(source.path().join("README.md"), "readme").unwrap();
+
+        archetect
+            .render_directory(
+                &Context::new(),
+                source.path(),
+                destination.path(),
+                &mut RulesContext::new(),
+            )
+            .unwrap();
+
+        assert!(destination.path().join("README.md").exists());
+        assert!(!destination.path().join(".git").exists());
+        assert!(!destination.path().join("build").exists());
+        assert!(!destination.path().join(".DS_Store").exists());
+        assert!(!destination.path().join(".archetectignore").exists());
+    }
+
+    #[test]
+    fn test_render_directory_honors_nested_ignore_files() {
+        let source = tempfile::tempdir().unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let archetect = Archetect::build().unwrap();
+        let web = source.path().join("web");
+        fs::create_dir_all(web.join("node_modules").join("left-pad")).unwrap();
+        fs::write(
+            web.join("node_modules").join("left-pad").join("index.js"),
+            "{{ missing }}",
+        )
+        .unwrap();
+        fs::write(web.join(".archetectignore"), "node_modules/\n!debug.log\n").unwrap();
+        fs::write(web.join("debug.log"), "log").unwrap();
+        fs::write(web.join("index.html"), "<html></html>").unwrap();
+        fs::create_dir(source.path().join("node_modules")).unwrap();
+        fs::write(source.path().join("node_modules").join("README.md"), "kept").unwrap();
+        fs::write(source.path().join("server.log"), "log").unwrap();
+        fs::write(source.path().join(".archetectignore"), "*.log\n").unwrap();
+
+        archetect
+            .render_directory(
+                &Context::new(),
+                source.path(),
+                destination.path(),
+                &mut RulesContext::new(),
+            )
+            .unwrap();
+
+        let web = destination.path().join("web");
+        assert!(web.join("index.html").exists());
+        assert!(web.join("debug.log").exists());
+        assert!(!web.join("node_modules").exists());
+        assert!(!web.join(".archetectignore").exists());
+        assert!(!destination.path().join("server.log").exists());
+        assert!(destination.path().join("node_modules").join("README.md").exists());
+    }
+
+    #[test]
+    fn test_strip_template_suffix() {
+        let suffixes = vec![".tera".to_owned(), ".j2".to_owned()];
+        assert_eq!(
+            strip_template_suffix(Path::new("src/main.rs.tera"), &suffixes),
+            Some("main.rs")
+        );
+        assert_eq!(
+            strip_template_suffix(Path::new("Dockerfile.j2"), &suffixes),
+            Some("Dockerfile")
+        );
+        assert_eq!(strip_template_suffix(Path::new("README.md"), &suffixes), None);
+        assert_eq!(strip_template_suffix(Path::new(".tera"), &suffixes), None);
+    }
+
+    #[test]
+    fn test_render_directory_with_template_suffixes() {
+        let source = tempfile::tempdir().unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let archetect = Archetect::build().unwrap();
+        let mut context = Context::new();
+        context.insert("name", "example");
+        fs::write(source.path().join("{{ name }}.md.tera"), "# {{ name }}").unwrap();
+        fs::write(source.path().join("config.yml.j2"), "name: {{ name }}").unwrap();
+        fs::write(source.path().join("action.yml"), "run: ${{ github.sha }}").unwrap();
+
+        let mut rules_context = RulesContext::new();
+        rules_context.set_template_suffixes(Some(vec![".tera".to_owned(), ".j2".to_owned()]));
+        archetect
+            .render_directory(&context, source.path(), destination.path(), &mut rules_context)
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(destination.path().join("example.md")).unwrap(),
+            "# example"
+        );
+        assert_eq!(
+            fs::read_to_string(destination.path().join("config.yml")).unwrap(),
+            "name: example"
+        );
+        assert_eq!(
+            fs::read_to_string(destination.path().join("action.yml")).unwrap(),
+            "run: ${{ github.sha }}"
+        );
+    }
+
+    #[test]
+    fn test_render_directory_skips_empty_names() {
+        let source = tempfile::tempdir().unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let archetect = Archetect::build().unwrap();
+        let mut context = Context::new();
+        context.insert("use_docker", &false);
+        context.insert("use_ci", &true);
+        let docker = source.path().join("{% if use_docker %}docker{% endif %}");
+        fs::create_dir(&docker).unwrap();
+        fs::write(docker.join("Dockerfile"), "FROM {{ missing }}").unwrap();
+        fs::write(
+            source.path().join("{% if use_docker %}.dockerignore{% endif %}"),
+            "target",
+        )
+        .unwrap();
+        fs::write(source.path().join("{% if use_ci %}ci.yml{% endif %}"), "ci").unwrap();
+
+        archetect
+            .render_directory(&context, source.path(), destination.path(), &mut RulesContext::new())
+            .unwrap();
+
+        let mut entries = fs::read_dir(destination.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect::<Vec<_>>();
+        entries.sort();
+        assert_eq!(entries, vec!["ci.yml"]);
+    }
+
+    #[test]
+    fn test_render_directory_skips_empty_files() {
+        let source = tempfile::tempdir().unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let archetect = Archetect::build().unwrap();
+        let mut context = Context::new();
+        context.insert("license", &false);
+        fs::write(source.path().join("LICENSE"), "{% if license %}MIT{% endif %}\n").unwrap();
+        fs::write(source.path().join("README.md"), "# Example").unwrap();
+
+        let mut rules_context = RulesContext::new();
+        archetect
+            .render_directory(&context, source.path(), destination.path(), &mut rules_context)
+            .unwrap();
+        assert!(destination.path().join("LICENSE").exists());
+
+        let destination = tempfile::tempdir().unwrap();
+        rules_context.set_skip_empty_files(true);
+        archetect
+            .render_directory(&context, source.path(), destination.path(), &mut rules_context)
+            .unwrap();
+        assert!(!destination.path().join("LICENSE").exists());
+        assert!(destination.path().join("README.md").exists());
+    }
+
+    #[test]
+    fn test_render_file_and_template() {
+        let source = tempfile::tempdir().unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let archetect = Archetect::build().unwrap();
+        let mut context = Context::new();
+        context.insert("name", "example");
+        fs::write(source.path().join("README.md"), "# {{ name }}").unwrap();
+        let readme = destination.path().join("docs").join("README.md");
+        let env = destination.path().join(".env");
+
+        let mut rules_context = RulesContext::new();
+        archetect
+            .render_file_with(
+                archetect.template_engine(),
+                &context,
+                source.path().join("README.md"),
+                &readme,
+                &rules_context,
+            )
+            .unwrap();
+        archetect
+            .render_template_with(
+                archetect.template_engine(),
+                &context,
+                "NAME={{ name }}",
+                &env,
+                &rules_context,
+            )
+            .unwrap();
+        assert_eq!(fs::read_to_string(&readme).unwrap(), "# example");
+        assert_eq!(fs::read_to_string(&env).unwrap(), "NAME=example");
+
+        context.insert("name", "changed");
+        archetect
+            .render_template_with(
+                archetect.template_engine(),
+                &context,
+                "NAME={{ name }}",
+                &env,
+                &rules_context,
+            )
+            .unwrap();
+        assert_eq!(fs::read_to_string(&env).unwrap(), "NAME=example");
+
+        rules_context.set_overwrite(true);
+        archetect
+            .render_template_with(
+                archetect.template_engine(),
+                &context,
+                "NAME={{ name }}",
+                &env,
+                &rules_context,
+            )
+            .unwrap();
+        assert_eq!(fs::read_to_string(&env).unwrap(), "NAME=changed");
+    }
+
+    #[test]
+    fn test_render_directory_collects_errors() {
+        let source = tempfile::tempdir().unwrap();
+        let destination = tempfile::tempdir().unwrap();
+        let archetect = Archetect::build().unwrap();
+        fs::create_dir(source.path().join("src")).unwrap();
+        for index in 0..20 {
+            fs::write(source.path().join("src").join(format!("{}.txt", index)), "{{ name }}").unwrap();
+        }
+        fs::write(source.path().join("broken-one.txt"), "{{ missing_one }}").unwrap();
+        fs::write(source.path().join("broken-two.txt"), "{{ missing_two }}").unwrap();
+
+        let mut context = Context::new();
+        context.insert("name", "example");
+        match archetect.render_directory(&context, source.path(), destination.path(), &mut RulesContext::new()) {
+            Err(RenderError::RenderErrors(errors)) => assert_eq!(errors.len(), 2),
+            _ => panic!("RenderErrors expected"),
+        }
+        for index in 0..20 {
+            let path = destination.path().join("src").join(format!("{}.txt", index));
+            assert_eq!(fs::read_to_string(path).unwrap(), "example");
+        }
+    }
 }
@@ -1,4 +1,6 @@
+use crate::actions::merge::MergeError;
 use crate::config::{AnswerConfigError, CatalogError};
+use crate::input::{MissingAnswer, PromptError};
 use crate::system::SystemError;
 use crate::util::SourceError;
 use crate::ArchetypeError;
@@ -6,13 +8,28 @@ use std::path::PathBuf;
 
 #[derive(Debug)]
 pub enum ArchetectError {
-    AnswerConfigError { source: String, cause: AnswerConfigError },
+    AnswerConfigError {
+        source: String,
+        cause: AnswerConfigError,
+    },
     ArchetypeError(ArchetypeError),
     GenericError(String),
     RenderError(RenderError),
     SystemError(SystemError),
     SourceError(SourceError),
     CatalogError(CatalogError),
+    MergeError(MergeError),
+    PromptError(PromptError),
+    /// Variables left without an answer while running headless, in the order they were encountered.
+    MissingAnswers(Vec<MissingAnswer>),
+    /// An answer supplied up front, rather than at a prompt, that is not of the variable's type or fails
+    /// its validation.
+    InvalidAnswer {
+        identifier: String,
+        message: String,
+    },
+    /// The run was abandoned when its answers were reviewed.
+    Aborted,
     IoError(std::io::Error),
 }
 
@@ -52,6 +69,18 @@ impl From<CatalogError> for ArchetectError {
     }
 }
 
+impl From<MergeError> for ArchetectError {
+    fn from(error: MergeError) -> Self {
+        ArchetectError::MergeError(error)
+    }
+}
+
+impl From<PromptError> for ArchetectError {
+    fn from(error: PromptError) -> Self {
+        ArchetectError::PromptError(error)
+    }
+}
+
 impl From<std::io::Error> for ArchetectError {
     fn from(error: std::io::Error) -> ArchetectError {
         ArchetectError::IoError(error)
@@ -63,6 +92,10 @@ pub enum RenderError {
     InvalidPathCharacters {
         source: PathBuf,
     },
+    InvalidSymlink {
+        source: PathBuf,
+        target: PathBuf,
+    },
     PathRenderError {
         source: PathBuf,
         error: crate::template_engine::Error,
@@ -88,6 +121,11 @@ pub enum RenderError {
         error: std::io::Error,
         message: String,
     },
+    IgnoreFileError {
+        source: PathBuf,
+        message: String,
+    },
+    RenderErrors(Vec<RenderError>),
 }
 
 impl From<std::io::Error> for RenderError {
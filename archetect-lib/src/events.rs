@@ -0,0 +1,136 @@
+use std::io::Write;
+use std::path::PathBuf;
+
+use log::{Level, LevelFilter, Metadata, Record};
+
+use crate::input::PromptRequest;
+use crate::report::{PathOutcome, RenderReport};
+
+/// Something that happened during a run, for front-ends driving archetect as a subprocess.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event")]
+pub enum Event {
+    #[serde(rename = "prompt")]
+    Prompt(PromptRequest),
+    #[serde(rename = "path")]
+    Path { path: PathBuf, outcome: PathOutcome },
+    #[serde(rename = "command-started")]
+    CommandStarted {
+        command: String,
+        args: Vec<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        directory: Option<PathBuf>,
+    },
+    #[serde(rename = "command-finished")]
+    CommandFinished {
+        command: String,
+        #[serde(rename = "exit-code", skip_serializing_if = "Option::is_none")]
+        exit_code: Option<i32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    #[serde(rename = "log")]
+    Log { level: String, message: String },
+    #[serde(rename = "error")]
+    Error { message: String },
+    /// The text of a `print` action.
+    #[serde(rename = "print")]
+    Print { message: String },
+    /// The help for a prompt, asked for by answering it with `?`. The prompt is then asked again.
+    #[serde(rename = "help")]
+    Help { prompt: String, help: String },
+    #[serde(rename = "report")]
+    Report(RenderReport),
+}
+
+pub trait EventListener: Send + Sync {
+    fn on_event(&self, event: &Event);
+}
+
+/// Writes every event to stdout as a line of JSON.
+pub struct JsonLinesEvents;
+
+impl EventListener for JsonLinesEvents {
+    fn on_event(&self, event: &Event) {
+        write_json_line(event);
+    }
+}
+
+/// Writes `event` to stdout as a single line of JSON, holding the stdout lock so lines written from
+/// different threads never interleave.
+pub fn write_json_line(event: &Event) {
+    if let Ok(line) = serde_json::to_string(event) {
+        let stdout = std::io::stdout();
+        let mut stdout = stdout.lock();
+        let _ = writeln!(stdout, "{}", line);
+        let _ = stdout.flush();
+    }
+}
+
+/// A logger writing log records as `log` events, and errors as `error` events, in place of plain
+/// console output.
+pub struct JsonLinesLogger {
+    level: LevelFilter,
+}
+
+impl JsonLinesLogger {
+    pub fn new(level: LevelFilter) -> JsonLinesLogger {
+        JsonLinesLogger { level }
+    }
+
+    pub fn init(self) -> Result<(), log::SetLoggerError> {
+        log::set_max_level(self.level);
+        log::set_boxed_logger(Box::new(self))
+    }
+}
+
+impl log::Log for JsonLinesLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message = record.args().to_string();
+        match record.level() {
+            Level::Error => write_json_line(&Event::Error { message }),
+            level => write_json_line(&Event::Log {
+                level: level.to_string().to_lowercase(),
+                message,
+            }),
+        }
+    }
+
+    fn flush(&self) {
+        let _ = std::io::stdout().flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        let event = Event::Path {
+            path: PathBuf::from("project/README.md"),
+            outcome: PathOutcome::Rendered,
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({ "event": "path", "path": "project/README.md", "outcome": "rendered" })
+        );
+
+        let event = Event::CommandFinished {
+            command: "git".to_owned(),
+            exit_code: Some(0),
+            error: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({ "event": "command-finished", "command": "git", "exit-code": 0 })
+        );
+    }
+}
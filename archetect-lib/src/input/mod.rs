@@ -2,9 +2,13 @@ use crate::config::{Catalog, CatalogEntry, CatalogError};
 
 use crate::util::{Source, SourceError};
 use crate::Archetect;
-use read_input::shortcut::input;
-use read_input::InputBuild;
-use std::collections::{HashMap, HashSet};
+
+mod prompter;
+
+pub use prompter::{
+    parse_bool, parse_list, FailingPrompter, JsonLinesPrompter, MissingAnswer, PromptError, PromptRequest, PromptType,
+    Prompter, ScriptedPrompter, TerminalPrompter,
+};
 
 #[derive(Debug)]
 pub enum CatalogSelectError {
@@ -19,11 +23,8 @@ impl From<SourceError> for CatalogSelectError {
     }
 }
 
-pub fn you_are_sure(message: &str) -> bool {
-    input::<bool>()
-        .msg(format!("{} [false]: ", message))
-        .default(false)
-        .get()
+pub fn you_are_sure(archetect: &Archetect, message: &str) -> Result<bool, PromptError> {
+    archetect.prompter().confirm(message, false)
 }
 
 pub fn select_from_catalog(
@@ -62,7 +63,7 @@ pub fn select_from_catalog(
 }
 
 pub fn select_from_entries(
-    _archetect: &Archetect,
+    archetect: &Archetect,
     mut entry_items: Vec<CatalogEntry>,
 ) -> Result<CatalogEntry, CatalogError> {
     if entry_items.is_empty() {
@@ -70,25 +71,13 @@ pub fn select_from_entries(
     }
 
     loop {
-        let mut choices = entry_items
+        let descriptions = entry_items
             .iter()
-            .enumerate()
-            .map(|(id, entry)| (id + 1, entry.clone()))
-            .collect::<HashMap<_, _>>();
-
-        for (id, entry) in entry_items.iter().enumerate() {
-            println!("{:>2}) {}", id + 1, entry.description());
-        }
-
-        let test_values = choices.keys().map(|v| *v).collect::<HashSet<_>>();
-        let result = input::<usize>()
-            .msg("\nSelect an entry: ")
-            .add_test(move |value| test_values.contains(value))
-            .err("Please enter the number of a selection from the list.")
-            .repeat_msg("Select an entry: ")
-            .get();
+            .map(|entry| entry.description().to_owned())
+            .collect::<Vec<_>>();
+        let index = archetect.prompter().select("Select an entry: ", &descriptions)?;
 
-        let choice = choices.remove(&result).unwrap();
+        let choice = entry_items.swap_remove(index);
 
         match choice {
             CatalogEntry::Group {
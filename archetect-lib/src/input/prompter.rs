@@ -0,0 +1,989 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::fs;
+use std::io::{BufRead, Write};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use read_input::prelude::*;
+use serde_json::Value;
+
+use crate::events::{write_json_line, Event};
+
+const ACCEPTABLE_BOOLEANS: [&str; 8] = ["y", "yes", "true", "t", "n", "no", "false", "f"];
+
+/// Asks the questions an archetype needs answered. Archetect prompts exclusively through the prompter
+/// it was built with, so front-ends other than a terminal can supply the answers.
+pub trait Prompter: Send + Sync {
+    fn prompt_string(&self, prompt: &str, default: Option<&str>, required: bool) -> Result<String, PromptError>;
+
+    fn prompt_int(&self, prompt: &str, default: Option<i64>) -> Result<i64, PromptError>;
+
+    fn prompt_float(&self, prompt: &str, default: Option<f64>) -> Result<f64, PromptError>;
+
+    fn prompt_bool(&self, prompt: &str, default: Option<bool>) -> Result<bool, PromptError>;
+
+    fn prompt_enum(&self, prompt: &str, options: &[String], default: Option<&str>) -> Result<String, PromptError>;
+
+    /// Asks for any number of `options`, with `defaults` selected when none are given. Without defaults,
+    /// a required prompt must be answered, if only with an empty selection.
+    fn prompt_multi_enum(
+        &self,
+        prompt: &str,
+        options: &[String],
+        defaults: &[String],
+        required: bool,
+    ) -> Result<Vec<String>, PromptError>;
+
+    fn prompt_list(&self, prompt: &str, required: bool) -> Result<Vec<String>, PromptError>;
+
+    /// Asks for text that may span several lines.
+    fn prompt_text(&self, prompt: &str, default: Option<&str>, required: bool) -> Result<String, PromptError>;
+
+    /// Asks for a value that must not be shown as it is entered, nor have a default.
+    fn prompt_secret(&self, prompt: &str, required: bool) -> Result<String, PromptError>;
+
+    /// Asks for one of `options`, returning its index.
+    fn select(&self, prompt: &str, options: &[String]) -> Result<usize, PromptError>;
+
+    fn confirm(&self, prompt: &str, default: bool) -> Result<bool, PromptError>;
+
+    /// Tells whoever is answering why their answer to `prompt` was rejected, before it is asked again.
+    /// Prompters that cannot ask again fail instead.
+    fn reject(&self, prompt: &str, message: &str) -> Result<(), PromptError>;
+
+    /// Shows the help for `prompt`, asked for by answering it with `?`, before it is asked again.
+    fn help(&self, prompt: &str, help: &str) -> Result<(), PromptError>;
+}
+
+#[derive(Debug)]
+pub enum PromptError {
+    /// The input was closed before an answer was given.
+    Closed,
+    /// No answer can be given to the prompt, such as when running without a prompter that may ask.
+    Unanswerable {
+        prompt: String,
+        prompt_type: PromptType,
+    },
+    InvalidAnswer {
+        prompt: String,
+        message: String,
+    },
+    /// The prompt was answered with `?`, asking for help with it. Selections and confirmations, which
+    /// have no help, take `?` as any other answer.
+    HelpRequested,
+    IoError(std::io::Error),
+}
+
+impl From<std::io::Error> for PromptError {
+    fn from(error: std::io::Error) -> Self {
+        PromptError::IoError(error)
+    }
+}
+
+/// Parses the boolean answers accepted at prompts, such as `y`, `no` or `true`.
+pub fn parse_bool(value: &str) -> Option<bool> {
+    let value = value.to_lowercase();
+    ACCEPTABLE_BOOLEANS
+        .iter()
+        .position(|i| i == &value.as_str())
+        .map(|position| position <= 3)
+}
+
+/// Parses a list given as `[a, b]`, or as comma separated items, such as `a, b`.
+pub fn parse_list(value: &str) -> Vec<String> {
+    let value = value.trim();
+    if value.starts_with('[') {
+        if let Ok(items) = serde_yaml::from_str::<Vec<String>>(value) {
+            return items;
+        }
+    }
+    value
+        .split(',')
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+        .map(|item| item.to_owned())
+        .collect()
+}
+
+/// Prompts on the terminal, re-asking until a valid answer is given. Prompts are written to stderr, leaving
+/// stdout to the output of the run.
+pub struct TerminalPrompter;
+
+impl Prompter for TerminalPrompter {
+    fn prompt_string(&self, prompt: &str, default: Option<&str>, required: bool) -> Result<String, PromptError> {
+        let mut prompt = prompt.to_owned();
+        if let Some(default) = default {
+            prompt.push_str(format!("[{}] ", default).as_str());
+        };
+        let mut input_builder = input::<String>().prompting_on_stderr().msg(&prompt);
+
+        if required {
+            input_builder = input_builder
+                .add_test(|value| !value.is_empty())
+                .repeat_msg(&prompt)
+                .err("Please provide a value.");
+        }
+
+        let value = if let Some(default) = default {
+            input_builder.default(default.to_owned()).get()
+        } else {
+            input_builder.get()
+        };
+        help_requested(value)
+    }
+
+    fn prompt_int(&self, prompt: &str, default: Option<i64>) -> Result<i64, PromptError> {
+        let mut prompt = prompt.to_owned();
+        if let Some(default) = default {
+            prompt.push_str(format!("[{}] ", default).as_str());
+        }
+
+        let input_builder = input::<String>()
+            .prompting_on_stderr()
+            .add_test(|value| is_help(value) || value.trim().parse::<i64>().is_ok())
+            .msg(&prompt)
+            .err("Please specify an integer.")
+            .repeat_msg(&prompt);
+
+        let value = if let Some(default) = default {
+            input_builder.default(default.to_string()).get()
+        } else {
+            input_builder.get()
+        };
+        Ok(help_requested(value)?.trim().parse::<i64>().unwrap_or_default())
+    }
+
+    fn prompt_float(&self, prompt: &str, default: Option<f64>) -> Result<f64, PromptError> {
+        let mut prompt = prompt.to_owned();
+        if let Some(default) = default {
+            prompt.push_str(format!("[{}] ", default).as_str());
+        }
+
+        let input_builder = input::<String>()
+            .prompting_on_stderr()
+            .add_test(|value| is_help(value) || value.trim().parse::<f64>().is_ok())
+            .msg(&prompt)
+            .err("Please specify a number.")
+            .repeat_msg(&prompt);
+
+        let value = if let Some(default) = default {
+            input_builder.default(default.to_string()).get()
+        } else {
+            input_builder.get()
+        };
+        Ok(help_requested(value)?.trim().parse::<f64>().unwrap_or_default())
+    }
+
+    fn prompt_bool(&self, prompt: &str, default: Option<bool>) -> Result<bool, PromptError> {
+        let mut prompt = prompt.to_owned();
+        if let Some(default) = default {
+            prompt.push_str(format!("[{}] ", default).as_str());
+        }
+
+        let input_builder = input::<String>()
+            .prompting_on_stderr()
+            .add_test(|value| is_help(value) || parse_bool(value).is_some())
+            .msg(&prompt)
+            .err(format!("Please specify a value of {:?}.", ACCEPTABLE_BOOLEANS))
+            .repeat_msg(&prompt);
+
+        let value = if let Some(default) = default {
+            input_builder.default(default.to_string()).get()
+        } else {
+            input_builder.get()
+        };
+        Ok(parse_bool(&help_requested(value)?).unwrap_or_default())
+    }
+
+    fn prompt_enum(&self, prompt: &str, options: &[String], default: Option<&str>) -> Result<String, PromptError> {
+        eprintln!("{}", prompt);
+        for (id, option) in options.iter().enumerate() {
+            eprintln!("{:>2}) {}", id + 1, option);
+        }
+
+        let default = default.and_then(|default| options.iter().position(|option| option == default));
+        let mut message = String::from("Select and entry: ");
+        if let Some(default) = default {
+            message.push_str(format!("[{}] ", options[default]).as_str());
+        };
+
+        let index = read_selection(&message, options.len(), default, true)?;
+        Ok(options[index].clone())
+    }
+
+    fn prompt_multi_enum(
+        &self,
+        prompt: &str,
+        options: &[String],
+        defaults: &[String],
+        _required: bool,
+    ) -> Result<Vec<String>, PromptError> {
+        eprintln!("{}", prompt);
+        for (id, option) in options.iter().enumerate() {
+            eprintln!("{:>2}) {}", id + 1, option);
+        }
+
+        let mut message = String::from("Select any entries, separated by commas: ");
+        if !defaults.is_empty() {
+            message.push_str(format!("[{}] ", defaults.join(", ")).as_str());
+        };
+
+        loop {
+            let value = help_requested(input::<String>().prompting_on_stderr().msg(&message).get())?;
+            if value.trim().is_empty() {
+                return Ok(defaults.to_vec());
+            }
+            let selections = parse_list(&value)
+                .iter()
+                .map(|selection| match selection.parse::<usize>() {
+                    Ok(number) if number >= 1 && number <= options.len() => Some(options[number - 1].clone()),
+                    _ => options.iter().find(|option| *option == selection).cloned(),
+                })
+                .collect::<Option<Vec<_>>>();
+            match selections {
+                Some(selections) => return Ok(selections),
+                None => eprintln!("Please enter the numbers of selections from the list."),
+            }
+        }
+    }
+
+    fn prompt_list(&self, prompt: &str, required: bool) -> Result<Vec<String>, PromptError> {
+        eprintln!("{}", prompt);
+
+        let mut results = vec![];
+        loop {
+            let count = results.len();
+            let mut input_builder = input::<String>().prompting_on_stderr().msg("Item: ");
+
+            if required {
+                input_builder = input_builder
+                    .add_test(move |value| count > 0 || !value.trim().is_empty())
+                    .err("This list requires at least one item.")
+                    .repeat_msg(" - ")
+            }
+            let item = help_requested(input_builder.get())?;
+
+            if item.trim().is_empty() {
+                break;
+            }
+            results.push(item);
+        }
+        Ok(results)
+    }
+
+    /// Opens the text in `$VISUAL` or `$EDITOR` when either is set. Otherwise, the text is read from
+    /// the terminal until a line holding only a `.`, or the end of input.
+    fn prompt_text(&self, prompt: &str, default: Option<&str>, required: bool) -> Result<String, PromptError> {
+        let editor = std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .ok()
+            .filter(|editor| !editor.trim().is_empty());
+
+        loop {
+            let text = match &editor {
+                Some(editor) => {
+                    eprintln!("{} (opening {})", prompt.trim(), editor);
+                    edit_text(editor, default.unwrap_or_default())?
+                }
+                None => {
+                    eprintln!("{} (end with a line holding only '.')", prompt.trim());
+                    help_requested(read_text()?)?
+                }
+            };
+            match default {
+                Some(default) if text.is_empty() => return Ok(default.to_owned()),
+                _ if required && text.is_empty() => eprintln!("Please provide a value."),
+                _ => return Ok(text),
+            }
+        }
+    }
+
+    fn prompt_secret(&self, prompt: &str, required: bool) -> Result<String, PromptError> {
+        loop {
+            let value = help_requested(rpassword::read_password_from_tty(Some(prompt))?)?;
+            if required && value.is_empty() {
+                eprintln!("Please provide a value.");
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn select(&self, prompt: &str, options: &[String]) -> Result<usize, PromptError> {
+        for (id, option) in options.iter().enumerate() {
+            eprintln!("{:>2}) {}", id + 1, option);
+        }
+        eprintln!();
+        read_selection(prompt, options.len(), None, false)
+    }
+
+    fn confirm(&self, prompt: &str, default: bool) -> Result<bool, PromptError> {
+        Ok(input::<bool>()
+            .prompting_on_stderr()
+            .msg(format!("{} [{}]: ", prompt, default))
+            .default(default)
+            .get())
+    }
+
+    fn reject(&self, _prompt: &str, message: &str) -> Result<(), PromptError> {
+        eprintln!("{}", message);
+        Ok(())
+    }
+
+    fn help(&self, _prompt: &str, help: &str) -> Result<(), PromptError> {
+        eprintln!("{}", help);
+        Ok(())
+    }
+}
+
+/// Whether an answer asks for help with its prompt.
+fn is_help(value: &str) -> bool {
+    value.trim() == "?"
+}
+
+/// Passes an answer through, unless it asks for help with its prompt.
+fn help_requested(value: String) -> Result<String, PromptError> {
+    if is_help(&value) {
+        Err(PromptError::HelpRequested)
+    } else {
+        Ok(value)
+    }
+}
+
+/// Reads lines until one holding only a `.`, or the end of input.
+fn read_text() -> Result<String, PromptError> {
+    let stdin = std::io::stdin();
+    let mut lines = vec![];
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim_end() == "." {
+            break;
+        }
+        lines.push(line);
+    }
+    Ok(lines.join("\n").trim_end().to_owned())
+}
+
+/// Edits `text` in a temporary file with `editor`, which may include arguments, such as `code --wait`.
+fn edit_text(editor: &str, text: &str) -> Result<String, PromptError> {
+    let mut file = tempfile::Builder::new().suffix(".txt").tempfile()?;
+    file.write_all(text.as_bytes())?;
+    file.flush()?;
+
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or(editor);
+    let status = Command::new(program).args(words).arg(file.path()).status()?;
+    if !status.success() {
+        return Err(PromptError::IoError(std::io::Error::other(format!(
+            "{} exited with {}",
+            editor, status
+        ))));
+    }
+    Ok(fs::read_to_string(file.path())?.trim_end().to_owned())
+}
+
+/// Reads the number of an entry in a listing of `count` entries, returning its index. With `help`,
+/// `?` may also be answered to ask for help.
+fn read_selection(message: &str, count: usize, default: Option<usize>, help: bool) -> Result<usize, PromptError> {
+    let test_values = (1..=count).collect::<HashSet<_>>();
+    let input_builder = input::<String>()
+        .prompting_on_stderr()
+        .msg(message)
+        .add_test(move |value| {
+            (help && is_help(value))
+                || value
+                    .trim()
+                    .parse::<usize>()
+                    .is_ok_and(|value| test_values.contains(&value))
+        })
+        .err("Please enter the number of a selection from the list.")
+        .repeat_msg(message);
+
+    let value = if let Some(default) = default {
+        input_builder.default((default + 1).to_string()).get()
+    } else {
+        input_builder.get()
+    };
+    Ok(help_requested(value)?.trim().parse::<usize>().unwrap_or(1) - 1)
+}
+
+/// The kind of answer a prompt asks for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub enum PromptType {
+    #[serde(rename = "string")]
+    String,
+    #[serde(rename = "int")]
+    Int,
+    #[serde(rename = "float")]
+    Float,
+    #[serde(rename = "bool")]
+    Bool,
+    #[serde(rename = "enum")]
+    Enum,
+    #[serde(rename = "multi-enum")]
+    MultiEnum,
+    #[serde(rename = "list")]
+    List,
+    #[serde(rename = "text")]
+    Text,
+    #[serde(rename = "secret")]
+    Secret,
+    #[serde(rename = "select")]
+    Select,
+    #[serde(rename = "confirm")]
+    Confirm,
+}
+
+impl fmt::Display for PromptType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            PromptType::String => "string",
+            PromptType::Int => "int",
+            PromptType::Float => "float",
+            PromptType::Bool => "bool",
+            PromptType::Enum => "enum",
+            PromptType::MultiEnum => "multi-enum",
+            PromptType::List => "list",
+            PromptType::Text => "text",
+            PromptType::Secret => "secret",
+            PromptType::Select => "select",
+            PromptType::Confirm => "confirm",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A variable left without an answer while running headless.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MissingAnswer {
+    identifier: String,
+    prompt: String,
+    #[serde(rename = "type")]
+    prompt_type: PromptType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+impl MissingAnswer {
+    pub fn new<I: Into<String>, P: Into<String>>(identifier: I, prompt: P, prompt_type: PromptType) -> MissingAnswer {
+        MissingAnswer {
+            identifier: identifier.into(),
+            prompt: prompt.into(),
+            prompt_type,
+            description: None,
+        }
+    }
+
+    pub fn with_description<D: Into<String>>(mut self, description: D) -> MissingAnswer {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn identifier(&self) -> &str {
+        &self.identifier
+    }
+
+    pub fn prompt(&self) -> &str {
+        &self.prompt
+    }
+
+    pub fn prompt_type(&self) -> PromptType {
+        self.prompt_type
+    }
+
+    /// The description of the variable, if it has one.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+/// A question, as sent to front-ends driving archetect through the JSON lines event protocol.
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptRequest {
+    id: usize,
+    #[serde(rename = "type")]
+    prompt_type: PromptType,
+    prompt: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default: Option<Value>,
+    required: bool,
+}
+
+impl PromptRequest {
+    fn new(prompt_type: PromptType, prompt: &str, default: Option<Value>, required: bool) -> PromptRequest {
+        PromptRequest {
+            id: 0,
+            prompt_type,
+            prompt: prompt.trim().to_owned(),
+            options: None,
+            default,
+            required,
+        }
+    }
+
+    fn with_options(mut self, options: &[String]) -> PromptRequest {
+        self.options = Some(options.to_vec());
+        self
+    }
+
+    pub fn prompt_type(&self) -> PromptType {
+        self.prompt_type
+    }
+
+    pub fn prompt(&self) -> &str {
+        &self.prompt
+    }
+
+    pub fn options(&self) -> Option<&[String]> {
+        self.options.as_deref()
+    }
+
+    pub fn default(&self) -> Option<&Value> {
+        self.default.as_ref()
+    }
+
+    pub fn required(&self) -> bool {
+        self.required
+    }
+
+    fn unanswerable(&self) -> PromptError {
+        PromptError::Unanswerable {
+            prompt: self.prompt.clone(),
+            prompt_type: self.prompt_type,
+        }
+    }
+
+    /// The answer to use when none is given: the default, or `null`.
+    fn default_answer(&self) -> Value {
+        self.default.clone().unwrap_or(Value::Null)
+    }
+
+    /// Whether `answer` asks for help with this prompt, which selections and confirmations have none of.
+    fn asks_for_help(&self, answer: &Value) -> bool {
+        match self.prompt_type {
+            PromptType::Select | PromptType::Confirm => false,
+            _ => answer.as_str().is_some_and(is_help),
+        }
+    }
+}
+
+/// A prompter answering with JSON values, which are converted to the type each prompt asks for.
+trait ValuePrompter: Send + Sync {
+    fn answer<T, F>(&self, request: PromptRequest, convert: F) -> Result<T, PromptError>
+    where
+        F: Fn(Value) -> Result<T, String>;
+
+    fn reject(&self, prompt: &str, message: &str) -> Result<(), PromptError>;
+
+    fn help(&self, prompt: &str, help: &str) -> Result<(), PromptError>;
+}
+
+impl<P: ValuePrompter> Prompter for P {
+    fn prompt_string(&self, prompt: &str, default: Option<&str>, required: bool) -> Result<String, PromptError> {
+        let request = PromptRequest::new(PromptType::String, prompt, default.map(Value::from), required);
+        self.answer(request, |answer| string_answer(answer, required))
+    }
+
+    fn prompt_int(&self, prompt: &str, default: Option<i64>) -> Result<i64, PromptError> {
+        let request = PromptRequest::new(PromptType::Int, prompt, default.map(Value::from), true);
+        self.answer(request, |answer| {
+            answer.as_i64().ok_or_else(|| "Please specify an integer.".to_owned())
+        })
+    }
+
+    fn prompt_float(&self, prompt: &str, default: Option<f64>) -> Result<f64, PromptError> {
+        let request = PromptRequest::new(PromptType::Float, prompt, default.map(Value::from), true);
+        self.answer(request, |answer| {
+            answer.as_f64().ok_or_else(|| "Please specify a number.".to_owned())
+        })
+    }
+
+    fn prompt_bool(&self, prompt: &str, default: Option<bool>) -> Result<bool, PromptError> {
+        let request = PromptRequest::new(PromptType::Bool, prompt, default.map(Value::from), true);
+        self.answer(request, bool_answer)
+    }
+
+    fn prompt_enum(&self, prompt: &str, options: &[String], default: Option<&str>) -> Result<String, PromptError> {
+        let default = default
+            .filter(|default| options.iter().any(|option| option == default))
+            .map(Value::from);
+        let request = PromptRequest::new(PromptType::Enum, prompt, default, true).with_options(options);
+        self.answer(request, |answer| match answer {
+            Value::String(value) if options.contains(&value) => Ok(value),
+            _ => Err(format!("Please select one of {:?}.", options)),
+        })
+    }
+
+    fn prompt_multi_enum(
+        &self,
+        prompt: &str,
+        options: &[String],
+        defaults: &[String],
+        required: bool,
+    ) -> Result<Vec<String>, PromptError> {
+        let defaults = defaults
+            .iter()
+            .filter(|default| options.contains(default))
+            .cloned()
+            .collect::<Vec<_>>();
+        // Without defaults, nothing is selected for a prompt left unanswered, unless it is required.
+        let default = Some(defaults).filter(|defaults| !defaults.is_empty()).map(Value::from);
+        let request = PromptRequest::new(PromptType::MultiEnum, prompt, default, required).with_options(options);
+        self.answer(request, |answer| {
+            let selections = match answer {
+                Value::Array(items) => items
+                    .into_iter()
+                    .map(|item| match item {
+                        Value::String(item) => Some(item),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>(),
+                Value::String(value) => Some(parse_list(&value)),
+                Value::Null if !required => Some(vec![]),
+                _ => None,
+            };
+            match selections {
+                Some(selections) if selections.iter().all(|selection| options.contains(selection)) => Ok(selections),
+                _ => Err(format!("Please select any of {:?}.", options)),
+            }
+        })
+    }
+
+    fn prompt_list(&self, prompt: &str, required: bool) -> Result<Vec<String>, PromptError> {
+        let request = PromptRequest::new(PromptType::List, prompt, None, required);
+        self.answer(request, |answer| match answer {
+            Value::Array(items) => {
+                let items = items
+                    .into_iter()
+                    .map(|item| match item {
+                        Value::String(item) => Ok(item),
+                        _ => Err("Please provide a list of strings.".to_owned()),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if required && items.is_empty() {
+                    Err("This list requires at least one item.".to_owned())
+                } else {
+                    Ok(items)
+                }
+            }
+            Value::Null if !required => Ok(vec![]),
+            _ => Err("Please provide a list of strings.".to_owned()),
+        })
+    }
+
+    fn prompt_text(&self, prompt: &str, default: Option<&str>, required: bool) -> Result<String, PromptError> {
+        let request = PromptRequest::new(PromptType::Text, prompt, default.map(Value::from), required);
+        self.answer(request, |answer| string_answer(answer, required))
+    }
+
+    fn prompt_secret(&self, prompt: &str, required: bool) -> Result<String, PromptError> {
+        let request = PromptRequest::new(PromptType::Secret, prompt, None, required);
+        self.answer(request, |answer| string_answer(answer, required))
+    }
+
+    fn select(&self, prompt: &str, options: &[String]) -> Result<usize, PromptError> {
+        let request = PromptRequest::new(PromptType::Select, prompt, None, true).with_options(options);
+        self.answer(request, |answer| match answer {
+            Value::String(value) => options
+                .iter()
+                .position(|option| option == &value)
+                .ok_or_else(|| format!("Please select one of {:?}.", options)),
+            _ => Err(format!("Please select one of {:?}.", options)),
+        })
+    }
+
+    fn confirm(&self, prompt: &str, default: bool) -> Result<bool, PromptError> {
+        let request = PromptRequest::new(PromptType::Confirm, prompt, Some(Value::from(default)), true);
+        self.answer(request, bool_answer)
+    }
+
+    fn reject(&self, prompt: &str, message: &str) -> Result<(), PromptError> {
+        ValuePrompter::reject(self, prompt, message)
+    }
+
+    fn help(&self, prompt: &str, help: &str) -> Result<(), PromptError> {
+        ValuePrompter::help(self, prompt, help)
+    }
+}
+
+fn string_answer(answer: Value, required: bool) -> Result<String, String> {
+    match answer {
+        Value::String(value) if required && value.is_empty() => Err("Please provide a value.".to_owned()),
+        Value::String(value) => Ok(value),
+        Value::Null if !required => Ok(String::new()),
+        _ => Err("Please provide a string.".to_owned()),
+    }
+}
+
+fn bool_answer(answer: Value) -> Result<bool, String> {
+    match answer {
+        Value::Bool(value) => Ok(value),
+        Value::String(value) => parse_bool(&value).ok_or_else(|| "Please specify a boolean.".to_owned()),
+        _ => Err("Please specify a boolean.".to_owned()),
+    }
+}
+
+/// Prompts by writing a `prompt` event to stdout and reading the answer back from stdin as a single
+/// line of JSON. A `null` answer accepts the default. Invalid answers are reported with an `error`
+/// event, and the prompt is repeated.
+pub struct JsonLinesPrompter {
+    next_id: AtomicUsize,
+}
+
+impl JsonLinesPrompter {
+    pub fn new() -> JsonLinesPrompter {
+        JsonLinesPrompter {
+            next_id: AtomicUsize::new(1),
+        }
+    }
+}
+
+impl Default for JsonLinesPrompter {
+    fn default() -> Self {
+        JsonLinesPrompter::new()
+    }
+}
+
+impl ValuePrompter for JsonLinesPrompter {
+    fn answer<T, F>(&self, mut request: PromptRequest, convert: F) -> Result<T, PromptError>
+    where
+        F: Fn(Value) -> Result<T, String>,
+    {
+        request.id = self.next_id.fetch_add(1, Ordering::SeqCst);
+
+        let stdin = std::io::stdin();
+        loop {
+            write_json_line(&Event::Prompt(request.clone()));
+
+            let mut line = String::new();
+            if stdin.lock().read_line(&mut line)? == 0 {
+                return Err(PromptError::Closed);
+            }
+            let answer = match serde_json::from_str::<Value>(line.trim()) {
+                Ok(Value::Null) => request.default_answer(),
+                Ok(answer) if request.asks_for_help(&answer) => return Err(PromptError::HelpRequested),
+                Ok(answer) => answer,
+                Err(error) => {
+                    write_json_line(&Event::Error {
+                        message: format!("Invalid JSON answer: {}", error),
+                    });
+                    continue;
+                }
+            };
+            match convert(answer) {
+                Ok(value) => return Ok(value),
+                Err(message) => write_json_line(&Event::Error { message }),
+            }
+        }
+    }
+    fn reject(&self, _prompt: &str, message: &str) -> Result<(), PromptError> {
+        write_json_line(&Event::Error {
+            message: message.to_owned(),
+        });
+        Ok(())
+    }
+
+    fn help(&self, prompt: &str, help: &str) -> Result<(), PromptError> {
+        write_json_line(&Event::Help {
+            prompt: prompt.trim().to_owned(),
+            help: help.to_owned(),
+        });
+        Ok(())
+    }
+}
+
+/// Answers prompts, in order, from a queue of answers given up front, such as in tests. A `null`
+/// answer accepts the default. Prompts beyond the end of the queue are unanswerable.
+pub struct ScriptedPrompter {
+    answers: Mutex<VecDeque<Value>>,
+}
+
+impl ScriptedPrompter {
+    pub fn new() -> ScriptedPrompter {
+        ScriptedPrompter {
+            answers: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn with_answer<V: Into<Value>>(self, answer: V) -> ScriptedPrompter {
+        self.add_answer(answer);
+        self
+    }
+
+    pub fn add_answer<V: Into<Value>>(&self, answer: V) {
+        self.answers.lock().unwrap().push_back(answer.into());
+    }
+
+    /// The number of answers not yet given.
+    pub fn remaining(&self) -> usize {
+        self.answers.lock().unwrap().len()
+    }
+}
+
+impl Default for ScriptedPrompter {
+    fn default() -> Self {
+        ScriptedPrompter::new()
+    }
+}
+
+impl ValuePrompter for ScriptedPrompter {
+    fn answer<T, F>(&self, request: PromptRequest, convert: F) -> Result<T, PromptError>
+    where
+        F: Fn(Value) -> Result<T, String>,
+    {
+        let answer = match self.answers.lock().unwrap().pop_front() {
+            Some(Value::Null) => request.default_answer(),
+            Some(answer) if request.asks_for_help(&answer) => return Err(PromptError::HelpRequested),
+            Some(answer) => answer,
+            None => return Err(request.unanswerable()),
+        };
+        convert(answer).map_err(|message| PromptError::InvalidAnswer {
+            prompt: request.prompt.clone(),
+            message,
+        })
+    }
+    /// Rejected answers are asked again, taking the next answer in the queue.
+    fn reject(&self, _prompt: &str, _message: &str) -> Result<(), PromptError> {
+        Ok(())
+    }
+
+    fn help(&self, _prompt: &str, _help: &str) -> Result<(), PromptError> {
+        Ok(())
+    }
+}
+
+/// Never asks. Prompts with a default are answered with it, and every other prompt fails as
+/// unanswerable. For running where nobody is present to answer.
+pub struct FailingPrompter;
+
+impl ValuePrompter for FailingPrompter {
+    fn answer<T, F>(&self, request: PromptRequest, convert: F) -> Result<T, PromptError>
+    where
+        F: Fn(Value) -> Result<T, String>,
+    {
+        convert(request.default_answer()).map_err(|_| request.unanswerable())
+    }
+    fn reject(&self, prompt: &str, message: &str) -> Result<(), PromptError> {
+        Err(PromptError::InvalidAnswer {
+            prompt: prompt.trim().to_owned(),
+            message: message.to_owned(),
+        })
+    }
+
+    fn help(&self, _prompt: &str, _help: &str) -> Result<(), PromptError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(
+            parse_list("postgres, kafka"),
+            vec!["postgres".to_owned(), "kafka".to_owned()]
+        );
+        assert_eq!(
+            parse_list(r#"["postgres","kafka"]"#),
+            vec!["postgres".to_owned(), "kafka".to_owned()]
+        );
+        assert_eq!(
+            parse_list("[postgres, kafka]"),
+            vec!["postgres".to_owned(), "kafka".to_owned()]
+        );
+        assert!(parse_list(" ").is_empty());
+    }
+
+    #[test]
+    fn test_parse_bool() {
+        assert_eq!(parse_bool("Yes"), Some(true));
+        assert_eq!(parse_bool("t"), Some(true));
+        assert_eq!(parse_bool("N"), Some(false));
+        assert_eq!(parse_bool("maybe"), None);
+    }
+
+    #[test]
+    fn test_scripted_prompter() {
+        let options = vec!["lib".to_owned(), "bin".to_owned()];
+        let prompter = ScriptedPrompter::new()
+            .with_answer("example")
+            .with_answer(42)
+            .with_answer("yes")
+            .with_answer(Value::Null)
+            .with_answer(json!(["orders", "customers"]))
+            .with_answer("bin");
+
+        assert_eq!(prompter.prompt_string("Name:", None, true).unwrap(), "example");
+        assert_eq!(prompter.prompt_int("Port:", Some(8080)).unwrap(), 42);
+        assert_eq!(prompter.prompt_bool("Docker?", None).unwrap(), true);
+        assert_eq!(prompter.prompt_enum("Kind:", &options, Some("lib")).unwrap(), "lib");
+        assert_eq!(
+            prompter.prompt_list("Services:", true).unwrap(),
+            vec!["orders".to_owned(), "customers".to_owned()]
+        );
+        assert_eq!(prompter.select("Select an entry:", &options).unwrap(), 1);
+        assert_eq!(prompter.remaining(), 0);
+
+        match prompter.prompt_string("Name:", None, true) {
+            Err(PromptError::Unanswerable { prompt, prompt_type }) => {
+                assert_eq!(prompt, "Name:");
+                assert_eq!(prompt_type, PromptType::String);
+            }
+            _ => panic!("Unanswerable expected"),
+        }
+    }
+
+    #[test]
+    fn test_scripted_prompter_invalid_answer() {
+        let prompter = ScriptedPrompter::new().with_answer("eight");
+        match prompter.prompt_int("Port:", None) {
+            Err(PromptError::InvalidAnswer { prompt, message: _ }) => assert_eq!(prompt, "Port:"),
+            _ => panic!("InvalidAnswer expected"),
+        }
+    }
+
+    #[test]
+    fn test_failing_prompter() {
+        let prompter = FailingPrompter;
+        assert_eq!(prompter.prompt_int("Port:", Some(8080)).unwrap(), 8080);
+        assert_eq!(prompter.prompt_string("Description:", None, false).unwrap(), "");
+        assert!(!prompter.confirm("Are you sure?", false).unwrap());
+        assert!(matches!(
+            prompter.prompt_string("Name:", None, true),
+            Err(PromptError::Unanswerable { .. })
+        ));
+        assert!(matches!(
+            prompter.select("Select an entry:", &["one".to_owned()]),
+            Err(PromptError::Unanswerable { .. })
+        ));
+    }
+
+    #[test]
+    fn test_failing_prompter_multi_enum() {
+        let prompter = FailingPrompter;
+        let options = vec!["postgres".to_owned(), "kafka".to_owned()];
+        assert_eq!(
+            prompter
+                .prompt_multi_enum("Services:", &options, &["kafka".to_owned()], true)
+                .unwrap(),
+            vec!["kafka".to_owned()]
+        );
+        assert!(prompter
+            .prompt_multi_enum("Services:", &options, &[], false)
+            .unwrap()
+            .is_empty());
+        match prompter.prompt_multi_enum("Services:", &options, &[], true) {
+            Err(PromptError::Unanswerable { prompt, prompt_type }) => {
+                assert_eq!(prompt, "Services:");
+                assert_eq!(prompt_type, PromptType::MultiEnum);
+            }
+            _ => panic!("Unanswerable expected"),
+        }
+    }
+}
@@ -17,9 +17,11 @@ pub mod heck;
 pub mod actions;
 pub mod archetype;
 pub mod config;
+pub mod events;
 pub mod input;
 pub mod loggerv;
 pub mod rendering;
+pub mod report;
 pub mod requirements;
 pub mod rules;
 pub mod system;
@@ -0,0 +1,189 @@
+use std::path::{Path, PathBuf};
+
+use linked_hash_map::LinkedHashMap;
+use serde_json::Value;
+
+/// What happened while running an archetype: every path written or left alone, every command run,
+/// and the final value of every variable that was set.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct RenderReport {
+    paths: Vec<PathReport>,
+    commands: Vec<CommandReport>,
+    answers: LinkedHashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PathReport {
+    path: PathBuf,
+    outcome: PathOutcome,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+pub enum PathOutcome {
+    #[serde(rename = "created")]
+    Created,
+    #[serde(rename = "rendered")]
+    Rendered,
+    #[serde(rename = "overwritten")]
+    Overwritten,
+    #[serde(rename = "preserved")]
+    Preserved,
+    #[serde(rename = "copied")]
+    Copied,
+    #[serde(rename = "linked")]
+    Linked,
+    #[serde(rename = "merged")]
+    Merged,
+    #[serde(rename = "skipped")]
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandReport {
+    command: String,
+    args: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    directory: Option<PathBuf>,
+    #[serde(rename = "exit-code", skip_serializing_if = "Option::is_none")]
+    exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl RenderReport {
+    pub fn new() -> RenderReport {
+        RenderReport::default()
+    }
+
+    pub fn paths(&self) -> &[PathReport] {
+        &self.paths
+    }
+
+    pub fn commands(&self) -> &[CommandReport] {
+        &self.commands
+    }
+
+    pub fn answers(&self) -> &LinkedHashMap<String, Value> {
+        &self.answers
+    }
+
+    /// The number of paths with the given outcome.
+    pub fn count(&self, outcome: PathOutcome) -> usize {
+        self.paths.iter().filter(|path| path.outcome == outcome).count()
+    }
+
+    pub fn add_path<P: Into<PathBuf>>(&mut self, path: P, outcome: PathOutcome) {
+        self.paths.push(PathReport {
+            path: path.into(),
+            outcome,
+        });
+    }
+
+    pub fn add_command(&mut self, command: CommandReport) {
+        self.commands.push(command);
+    }
+
+    pub fn add_answer<K: Into<String>>(&mut self, identifier: K, value: Value) {
+        self.answers.insert(identifier.into(), value);
+    }
+
+    /// Folds the paths and commands of a nested archetype's report into this one. The nested answers
+    /// are not carried over, as they are scoped to the nested archetype.
+    pub fn absorb(&mut self, nested: RenderReport) {
+        self.paths.extend(nested.paths);
+        self.commands.extend(nested.commands);
+    }
+}
+
+impl PathReport {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn outcome(&self) -> PathOutcome {
+        self.outcome
+    }
+}
+
+impl CommandReport {
+    pub fn new<C: Into<String>>(command: C, args: Vec<String>, directory: Option<PathBuf>) -> CommandReport {
+        CommandReport {
+            command: command.into(),
+            args,
+            directory,
+            exit_code: None,
+            error: None,
+        }
+    }
+
+    pub fn with_exit_code(mut self, exit_code: Option<i32>) -> CommandReport {
+        self.exit_code = exit_code;
+        self
+    }
+
+    pub fn with_error<E: Into<String>>(mut self, error: E) -> CommandReport {
+        self.error = Some(error.into());
+        self
+    }
+
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Whether the command could be run and exited with a zero status.
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        let mut report = RenderReport::new();
+        report.add_path("project/README.md", PathOutcome::Rendered);
+        report.add_path("project/.gitignore", PathOutcome::Preserved);
+        report.add_command(CommandReport::new("git", vec!["init".to_owned()], None).with_exit_code(Some(0)));
+        report.add_answer("name", Value::from("example"));
+
+        assert_eq!(report.count(PathOutcome::Rendered), 1);
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({
+                "paths": [
+                    { "path": "project/README.md", "outcome": "rendered" },
+                    { "path": "project/.gitignore", "outcome": "preserved" },
+                ],
+                "commands": [{ "command": "git", "args": ["init"], "exit-code": 0 }],
+                "answers": { "name": "example" },
+            })
+        );
+    }
+
+    #[test]
+    fn test_absorb() {
+        let mut report = RenderReport::new();
+        report.add_answer("name", Value::from("example"));
+        let mut nested = RenderReport::new();
+        nested.add_path("project/service/main.rs", PathOutcome::Rendered);
+        nested.add_answer("service", Value::from("orders"));
+
+        report.absorb(nested);
+        assert_eq!(report.paths().len(), 1);
+        assert_eq!(report.answers().len(), 1);
+    }
+}
@@ -8,17 +8,23 @@ use crate::config::{Pattern, RuleAction, RuleConfig};
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RulesContext {
     overwrite: bool,
+    #[serde(default)]
+    skip_empty_files: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     path_rules: Option<LinkedHashMap<String, RuleConfig>>,
     break_triggered: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    template_suffixes: Option<Vec<String>>,
 }
 
 impl RulesContext {
     pub fn new() -> RulesContext {
         RulesContext {
             overwrite: false,
+            skip_empty_files: false,
             path_rules: None,
             break_triggered: false,
+            template_suffixes: None,
         }
     }
 
@@ -30,6 +36,15 @@ impl RulesContext {
         self.overwrite
     }
 
+    pub fn set_skip_empty_files(&mut self, skip_empty_files: bool) {
+        self.skip_empty_files = skip_empty_files;
+    }
+
+    /// Whether rendered files whose contents are empty or whitespace are left unwritten.
+    pub fn skip_empty_files(&self) -> bool {
+        self.skip_empty_files
+    }
+
     pub fn path_rules_mut(&mut self) -> Option<&mut LinkedHashMap<String, RuleConfig>> {
         self.path_rules.as_mut()
     }
@@ -46,6 +61,15 @@ impl RulesContext {
         self.break_triggered = break_triggered;
     }
 
+    pub fn set_template_suffixes(&mut self, template_suffixes: Option<Vec<String>>) {
+        self.template_suffixes = template_suffixes;
+    }
+
+    /// The template suffixes of the archetype being rendered, if it opted into suffix mode.
+    pub fn template_suffixes(&self) -> Option<&[String]> {
+        self.template_suffixes.as_deref()
+    }
+
     pub fn insert_path_rules(&mut self, insert: &LinkedHashMap<String, RuleConfig>) {
         let mut results = insert.clone();
         let path_rules = self.path_rules.get_or_insert_with(|| LinkedHashMap::new());
@@ -63,6 +87,11 @@ impl RulesContext {
     }
 
     pub fn get_source_action<P: AsRef<Path>>(&self, path: P) -> RuleAction {
+        self.find_source_action(path).unwrap_or_default()
+    }
+
+    /// Returns the action of the first source rule matching `path`, or `None` if no rule matched.
+    pub fn find_source_action<P: AsRef<Path>>(&self, path: P) -> Option<RuleAction> {
         if let Some(path_rules) = self.path_rules() {
             let path = path.as_ref();
             for (name, path_rule) in path_rules {
@@ -78,7 +107,7 @@ impl RulesContext {
                                     pattern,
                                     path.display()
                                 );
-                                return path_rule.action().clone();
+                                return Some(path_rule.action().clone());
                             }
                         }
                         _ => unimplemented!(),
@@ -86,7 +115,7 @@ impl RulesContext {
                 }
             }
         }
-        RuleAction::RENDER
+        None
     }
 }
 
@@ -11,7 +11,7 @@ pub enum LayoutType {
     Temp,
 }
 
-pub trait SystemLayout {
+pub trait SystemLayout: Send + Sync {
     fn configs_dir(&self) -> PathBuf;
 
     fn cache_dir(&self) -> PathBuf;
@@ -62,6 +62,10 @@ impl Context {
     pub fn get(&self, identifier: &str) -> Option<&Value> {
         self.data.get(identifier)
     }
+
+    pub fn remove(&mut self, identifier: &str) -> Option<Value> {
+        self.data.remove(identifier)
+    }
 }
 
 impl Default for Context {
@@ -160,3 +160,33 @@ fn render_super_in_grandchild_without_redefining_in_parent_works() {
     let result = tera.render("child", Context::new());
     assert_eq!(result.unwrap(), "Title - More".to_string());
 }
+
+#[test]
+fn render_string_extending_loaded_templates() {
+    let mut tera = Tera::default();
+    tera.add_raw_templates(vec![
+        (
+            "grandparent",
+            "{% block title %}Title{% endblock %}{% block body %}{% endblock %}",
+        ),
+        (
+            "parent",
+            "{% extends \"grandparent\" %}{% block body %}Body{% endblock %}",
+        ),
+    ])
+    .unwrap();
+
+    let result = tera.render_string(
+        "{% extends \"parent\" %}{% block title %}{{ super() }} - More{% endblock %}",
+        Context::new(),
+    );
+    assert_eq!(result.unwrap(), "Title - MoreBody".to_string());
+}
+
+#[test]
+fn render_string_extending_missing_template_errors() {
+    let tera = Tera::default();
+
+    let result = tera.render_string("{% extends \"missing\" %}", Context::new());
+    assert!(result.is_err());
+}
@@ -354,12 +354,52 @@ impl Tera {
     /// context.insert("subject", "world");
     /// tera.render_one_off("Hello, {{ subject }}", context);
     /// ```
+    ///
+    /// The string may `include`, `import` macros from and `extend` the templates loaded in this instance.
     pub fn render_string<C: Into<Value>>(&self, template_contents: &str, context: C) -> Result<String> {
-        let template = Template::new("one_off", None, template_contents)
+        let mut template = Template::new("one_off", None, template_contents)
             .map_err(|e| Error::chain(format!("Failed to parse template string: '{}'", template_contents), e))?;
+        self.build_one_off_inheritance_chain(&mut template)?;
         self.render_template(&template, context)
     }
 
+    // Builds the inheritance chain of a template that isn't loaded in this instance against the ones
+    // that are. The loaded templates already had their own chains checked for circular extends.
+    fn build_one_off_inheritance_chain(&self, template: &mut Template) -> Result<()> {
+        if template.parent.is_none() && template.blocks.is_empty() {
+            return Ok(());
+        }
+
+        let mut parents = vec![];
+        let mut parent_name = template.parent.clone();
+        let mut child_name = template.name.clone();
+        while let Some(name) = parent_name {
+            let parent = self
+                .templates
+                .get(&name)
+                .ok_or_else(|| Error::missing_parent(&child_name, &name))?;
+            parents.push(parent.name.clone());
+            parent_name = parent.parent.clone();
+            child_name = name;
+        }
+
+        let mut blocks_definitions = HashMap::new();
+        for (block_name, def) in &template.blocks {
+            let mut definitions = vec![(template.name.clone(), def.clone())];
+            for parent in &parents {
+                let t = self.get_template(parent)?;
+                if let Some(b) = t.blocks.get(block_name) {
+                    definitions.push((t.name.clone(), b.clone()));
+                }
+            }
+            blocks_definitions.insert(block_name.clone(), definitions);
+        }
+
+        template.parents = parents;
+        template.blocks_definitions = blocks_definitions;
+        Ok(())
+    }
+
     /// Renders a one off template (for example a template coming from a user input) given a `Context`
     ///
     /// This creates a separate instance of Tera with no possibilities of adding custom filters
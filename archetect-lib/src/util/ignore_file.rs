@@ -0,0 +1,166 @@
+use std::path::{Path, PathBuf};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+use crate::RenderError;
+
+pub const IGNORE_FILE_NAME: &str = ".archetectignore";
+
+/// Patterns ignored in every content directory. An `.archetectignore` file may re-include any of them
+/// with a negated pattern.
+pub const DEFAULT_IGNORES: &[&str] = &[
+    ".git/",
+    ".hg/",
+    ".svn/",
+    ".DS_Store",
+    "Thumbs.db",
+    "desktop.ini",
+    "*~",
+    ".*.swp",
+    ".*.swo",
+    ".#*",
+    IGNORE_FILE_NAME,
+];
+
+/// The ignore rules of a content directory: the built-in defaults, followed by the patterns of the
+/// `.archetectignore` file at its root, using gitignore semantics. Ignore files further down the tree are
+/// stacked over these with `nested`, their patterns taking precedence within their own directory.
+#[derive(Clone)]
+pub struct IgnoreFile {
+    matchers: Vec<Gitignore>,
+}
+
+impl IgnoreFile {
+    pub fn load<P: AsRef<Path>>(root: P) -> Result<IgnoreFile, RenderError> {
+        let root = root.as_ref();
+        let mut builder = GitignoreBuilder::new(root);
+        for pattern in DEFAULT_IGNORES {
+            builder
+                .add_line(None, pattern)
+                .map_err(|error| ignore_file_error(root, error))?;
+        }
+
+        let ignore_file = root.join(IGNORE_FILE_NAME);
+        if ignore_file.is_file() {
+            if let Some(error) = builder.add(&ignore_file) {
+                return Err(ignore_file_error(&ignore_file, error));
+            }
+        }
+
+        let matcher = builder
+            .build()
+            .map_err(|error| ignore_file_error(&ignore_file, error))?;
+        Ok(IgnoreFile {
+            matchers: vec![matcher],
+        })
+    }
+
+    /// The rules for `directory`, a directory within this one: these, with the patterns of its own
+    /// `.archetectignore` file stacked over them, or `None` when it has no ignore file of its own.
+    pub fn nested<P: AsRef<Path>>(&self, directory: P) -> Result<Option<IgnoreFile>, RenderError> {
+        let directory = directory.as_ref();
+        let ignore_file = directory.join(IGNORE_FILE_NAME);
+        if !ignore_file.is_file() {
+            return Ok(None);
+        }
+
+        let mut builder = GitignoreBuilder::new(directory);
+        if let Some(error) = builder.add(&ignore_file) {
+            return Err(ignore_file_error(&ignore_file, error));
+        }
+        let matcher = builder
+            .build()
+            .map_err(|error| ignore_file_error(&ignore_file, error))?;
+
+        let mut ignores = self.clone();
+        ignores.matchers.push(matcher);
+        Ok(Some(ignores))
+    }
+
+    /// Whether `path` is ignored, as decided by the deepest ignore file with a pattern matching it. Parent
+    /// directories are not consulted, as ignored directories are never descended into.
+    pub fn is_ignored<P: AsRef<Path>>(&self, path: P, is_dir: bool) -> bool {
+        let path = path.as_ref();
+        for matcher in self.matchers.iter().rev() {
+            let matched = matcher.matched(path, is_dir);
+            if !matched.is_none() {
+                return matched.is_ignore();
+            }
+        }
+        false
+    }
+}
+
+fn ignore_file_error(source: &Path, error: ignore::Error) -> RenderError {
+    RenderError::IgnoreFileError {
+        source: PathBuf::from(source),
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_default_ignores() {
+        let root = tempfile::tempdir().unwrap();
+        let ignores = IgnoreFile::load(root.path()).unwrap();
+
+        assert!(ignores.is_ignored(root.path().join(".git"), true));
+        assert!(ignores.is_ignored(root.path().join("src/.DS_Store"), false));
+        assert!(ignores.is_ignored(root.path().join("README.md~"), false));
+        assert!(ignores.is_ignored(root.path().join(".README.md.swp"), false));
+        assert!(ignores.is_ignored(root.path().join(IGNORE_FILE_NAME), false));
+        assert!(!ignores.is_ignored(root.path().join(".gitignore"), false));
+        assert!(!ignores.is_ignored(root.path().join(".git"), false));
+    }
+
+    #[test]
+    fn test_ignore_file() {
+        let root = tempfile::tempdir().unwrap();
+        fs::write(
+            root.path().join(IGNORE_FILE_NAME),
+            indoc!(
+                r#"
+                # Generated output
+                target/
+                *.log
+                !keep.log
+                !.DS_Store
+                "#
+            ),
+        )
+        .unwrap();
+        let ignores = IgnoreFile::load(root.path()).unwrap();
+
+        assert!(ignores.is_ignored(root.path().join("target"), true));
+        assert!(!ignores.is_ignored(root.path().join("target"), false));
+        assert!(ignores.is_ignored(root.path().join("logs/debug.log"), false));
+        assert!(!ignores.is_ignored(root.path().join("keep.log"), false));
+        assert!(!ignores.is_ignored(root.path().join(".DS_Store"), false));
+        assert!(ignores.is_ignored(root.path().join(".git"), true));
+    }
+
+    #[test]
+    fn test_nested_ignore_file() {
+        let root = tempfile::tempdir().unwrap();
+        let web = root.path().join("web");
+        fs::create_dir(&web).unwrap();
+        fs::write(root.path().join(IGNORE_FILE_NAME), "*.log\n").unwrap();
+        fs::write(web.join(IGNORE_FILE_NAME), "node_modules/\n!keep.log\n").unwrap();
+        let ignores = IgnoreFile::load(root.path()).unwrap();
+
+        assert!(ignores.nested(root.path().join("src")).unwrap().is_none());
+        let nested = ignores.nested(&web).unwrap().unwrap();
+        assert!(nested.is_ignored(web.join("node_modules"), true));
+        assert!(nested.is_ignored(web.join("debug.log"), false));
+        assert!(!nested.is_ignored(web.join("keep.log"), false));
+        assert!(nested.is_ignored(web.join(".DS_Store"), false));
+        assert!(!ignores.is_ignored(root.path().join("node_modules"), true));
+    }
+}
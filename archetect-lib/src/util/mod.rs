@@ -1,3 +1,5 @@
+mod ignore_file;
 mod source;
 
+pub use ignore_file::{IgnoreFile, DEFAULT_IGNORES, IGNORE_FILE_NAME};
 pub use source::{Source, SourceError};